@@ -5,7 +5,7 @@ use pathfinding::directed::dfs::dfs_reach;
 
 use crate::board::Board;
 use crate::clue::Clue;
-use crate::vocab::{load_cached_vocab, Vocab};
+use crate::vocab::{load_vocab, Vocab};
 
 pub struct Puzzle {
     board: Board,
@@ -23,7 +23,7 @@ impl TryFrom<Board> for Puzzle {
             return Err("Clues must have length at least 3.".into());
         }
 
-        let vocab = load_cached_vocab()?;
+        let vocab = load_vocab();
 
         Ok(Self {
             board,
@@ -1,42 +1,55 @@
-use std::{collections::HashMap, error::Error, fs};
-
-use itertools::Itertools;
+use prefix_tree::{PrefixTree, Slot};
 
 use crate::{square::Square, word::Word};
 
-const SERDE_VOCAB_PATH: &'static str = "./crossword-vocab.serde";
-const TXT_VOCAB_PATH: &'static str = "../word_list.txt";
+const WORD_LIST: &str = include_str!("../../word_list.txt");
 
 type Score = usize;
 
-/// Thin wrapper around a ternary search tree
-pub(crate) struct Vocab(tst::TSTMap<Score>);
+/// Thin wrapper around a prefix tree, keyed on the uppercase bytes of each
+/// word
+pub(crate) struct Vocab(PrefixTree<u8, (Word, Score)>);
 
 impl Vocab {
     pub(crate) fn new<'a, T>(word_list: T) -> Self
     where
         T: IntoIterator<Item = (&'a str, Score)>,
     {
-        let mut tree = tst::TSTMap::new();
+        let mut tree = PrefixTree::empty();
 
         for (word, score) in word_list {
-            tree.insert(word, score);
+            if let Ok(word) = Word::try_from(word) {
+                let key = word.chars.to_vec();
+                tree.set(key, (word, score));
+            }
         }
 
         Vocab(tree)
     }
 
-    pub(crate) fn matches(&'_ self, squares: Vec<Square>) -> &'_ [Word] {
-        let matching_words = self
-            .vocab
-            .get(&squares)
-            .map(|v| v.as_slice()) // convert vec to slice
-            .unwrap_or(&[]); // return empty slice if there aren't matches
-
-        matching_words
+    /// All the words that fit `squares`, where empty squares match any letter
+    pub(crate) fn matches(&'_ self, squares: Vec<Square>) -> Vec<&'_ Word> {
+        let pattern = squares.iter().map(|square| match square.is_empty() {
+            true => Slot::Any,
+            false => Slot::Exact(square.as_char() as u8),
+        });
+
+        self.0
+            .matches(pattern)
+            .into_iter()
+            .map(|(_, (word, _))| word)
+            .collect()
     }
 }
 
+/// The vocabulary of the shared word list, which has the most common words
+/// first. Each word is scored by its rank.
+pub(crate) fn load_vocab() -> Vocab {
+    let words = WORD_LIST.lines().map(str::trim);
+
+    Vocab::new(words.enumerate().map(|(rank, word)| (word, rank)))
+}
+
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
@@ -59,7 +72,7 @@ mod tests {
                 })
                 .collect_vec();
 
-        let vocab = Vocab::new(words);
+        let vocab = Vocab::new(words.map(|word| (word, 0)));
 
         // check that all the partial words are in the vocab
         assert!(partial_words
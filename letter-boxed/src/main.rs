@@ -2,14 +2,18 @@ use derivative::Derivative;
 use regex::Regex;
 use std::{
     error::Error,
+    fs::File,
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{BufReader, BufWriter, Read, Write},
     ops::{BitOr, Index, IndexMut},
+    path::{Path, PathBuf},
     time::Instant,
 };
 
 use itertools::Itertools;
 
 use pathfinding::directed::{dfs::dfs_reach, dijkstra::dijkstra_reach};
-use prefix_tree::{PrefixTree, QueryResult};
+use prefix_tree::{Cursor, PrefixTree, QueryResult};
 
 const N_PER_SIDE: usize = 3;
 const N_SIDES: usize = 4;
@@ -82,13 +86,31 @@ struct GameState {
     moves: Vec<String>,
 }
 
+/// A word being spelled out on the board, along with where it has gotten to
+/// in the vocabulary.
+#[derive(Derivative, Clone)]
+#[derivative(PartialEq, Eq, Hash)]
+struct PartialWord<'a> {
+    pos: Position,
+    word: String,
+    letters_used: Board<bool>,
+
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    cursor: Cursor<'a, Letter, usize>,
+}
+
 pub struct LettersBoxedGame {
     letters: Board<Letter>,
     vocabulary: Vocabulary,
 }
 
 impl LettersBoxedGame {
-    pub fn new(sides: &[&str]) -> Result<Self, Box<dyn Error>> {
+    /// `vocab_cache` is where to keep the vocabulary between runs, if
+    /// anywhere.
+    pub fn new(
+        sides: &[&str],
+        vocab_cache: Option<&Path>,
+    ) -> Result<Self, Box<dyn Error>> {
         let mut letters: Board<Letter> = Default::default();
 
         sides.into_iter().enumerate().for_each(|(i, side)| {
@@ -99,16 +121,19 @@ impl LettersBoxedGame {
 
         Ok(Self {
             letters,
-            vocabulary: Self::load_vocabulary(),
+            vocabulary: Self::load_vocabulary(vocab_cache),
         })
     }
 
-    pub fn today() -> Result<Self, Box<dyn Error>> {
+    pub fn today(vocab_cache: Option<&Path>) -> Result<Self, Box<dyn Error>> {
         let html = reqwest::blocking::get(NYTIMES_GAMES_URL)?.text()?;
-        Self::from_nytimes_html(&html)
+        Self::from_nytimes_html(&html, vocab_cache)
     }
 
-    fn from_nytimes_html(html: &str) -> Result<Self, Box<dyn Error>> {
+    fn from_nytimes_html(
+        html: &str,
+        vocab_cache: Option<&Path>,
+    ) -> Result<Self, Box<dyn Error>> {
         let pattern = Regex::new(r#"\"sides\":\[(.*?)\]"#)?;
 
         let sides = pattern
@@ -121,13 +146,63 @@ impl LettersBoxedGame {
             .filter_map(|side| side.get(1..4))
             .collect_vec();
 
-        Self::new(&sides)
+        Self::new(&sides, vocab_cache)
+    }
+
+    /// Loads the vocabulary from the snapshot at `cache`, building (and
+    /// caching) it from the word list if there isn't one or it was built from
+    /// different inputs.
+    fn load_vocabulary(cache: Option<&Path>) -> Vocabulary {
+        let Some(cache) = cache else {
+            return Self::build_vocabulary();
+        };
+
+        if let Ok(vocab) = Self::read_vocabulary(cache) {
+            return vocab;
+        }
+
+        let vocab = Self::build_vocabulary();
+
+        // failing to cache just means we rebuild next time
+        let _ = Self::write_vocabulary(cache, &vocab);
+
+        vocab
+    }
+
+    /// Identifies the inputs to `build_vocabulary`, so that a cache built
+    /// from other ones is never used.
+    fn vocab_fingerprint() -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (WORD_LIST, VOCAB_SIZE, WORD_LEN_THRESHOLD).hash(&mut hasher);
+        hasher.finish()
     }
 
-    fn load_vocabulary() -> Vocabulary {
-        let mut vocab: Vocabulary = PrefixTree::empty();
+    /// A cache is the fingerprint of its inputs followed by the snapshot.
+    fn read_vocabulary(path: &Path) -> Result<Vocabulary, Box<dyn Error>> {
+        let mut reader = BufReader::new(File::open(path)?);
 
-        for (word, popularity) in WORD_LIST
+        let mut fingerprint = [0; 8];
+        reader.read_exact(&mut fingerprint)?;
+        if u64::from_le_bytes(fingerprint) != Self::vocab_fingerprint() {
+            return Err("Vocabulary cache is out of date.".into());
+        }
+
+        Vocabulary::read_snapshot(reader)
+    }
+
+    fn write_vocabulary(
+        path: &Path,
+        vocab: &Vocabulary,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&Self::vocab_fingerprint().to_le_bytes())?;
+        vocab.write_snapshot(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn build_vocabulary() -> Vocabulary {
+        WORD_LIST
             .split("\n")
             .map(|line| {
                 let mut line = line.split_whitespace();
@@ -138,11 +213,8 @@ impl LettersBoxedGame {
             })
             .filter(|(word, _)| word.len() >= WORD_LEN_THRESHOLD)
             .take(VOCAB_SIZE)
-        {
-            vocab.set(word.chars(), popularity);
-        }
-
-        vocab
+            .map(|(word, popularity)| (word.chars().collect_vec(), popularity))
+            .collect()
     }
 
     pub fn solve(&self) -> impl IntoIterator<Item = Vec<String>> {
@@ -231,7 +303,12 @@ impl LettersBoxedGame {
             Default::default();
 
         for starting_pos in Self::all_moves() {
-            let starting_word = self.letters[starting_pos].to_string();
+            let starting_char = self.letters[starting_pos];
+
+            let Some(cursor) = self.vocabulary.cursor().step(&starting_char)
+            else {
+                continue; // no words start with this letter
+            };
 
             let mut starting_letters: Board<bool> = Default::default();
             starting_letters[starting_pos] = true;
@@ -239,31 +316,35 @@ impl LettersBoxedGame {
             // generate all reachable positions
             let reachable = dfs_reach(
                 // initial state
-                (starting_pos, starting_word, starting_letters),
+                PartialWord {
+                    pos: starting_pos,
+                    word: starting_char.to_string(),
+                    letters_used: starting_letters,
+                    cursor,
+                },
                 // closure to generate possible next letters
-                move |(current_pos, current_word, current_letters)| {
-                    Self::valid_moves(current_pos.clone())
+                move |current| {
+                    Self::valid_moves(current.pos)
                         .filter_map(|next_pos| {
                             let next_char = self.letters[next_pos];
-                            let next_word_chars = current_word
-                                .chars()
-                                .chain(std::iter::once(next_char));
-
-                            match self.vocabulary.get(next_word_chars) {
-                                prefix_tree::QueryResult::NotFound => None,
-                                _ => {
-                                    // update the word
-                                    let mut next_word = current_word.clone();
-                                    next_word.push(next_char);
-
-                                    // update the letters used
-                                    let mut next_letters =
-                                        current_letters.clone();
-                                    next_letters[next_pos] = true;
-
-                                    Some((next_pos, next_word, next_letters))
-                                }
-                            }
+
+                            // extend the word by a single letter
+                            let cursor = current.cursor.step(&next_char)?;
+
+                            // update the word
+                            let mut word = current.word.clone();
+                            word.push(next_char);
+
+                            // update the letters used
+                            let mut letters_used = current.letters_used.clone();
+                            letters_used[next_pos] = true;
+
+                            Some(PartialWord {
+                                pos: next_pos,
+                                word,
+                                letters_used,
+                                cursor,
+                            })
                         })
                         .collect_vec()
                 },
@@ -271,11 +352,11 @@ impl LettersBoxedGame {
 
             // keep only those which are valid words
             let all_reachable_words = reachable
-                .filter(|(_end_pos, word, _letters)| {
-                    match self.vocabulary.get(word.chars()) {
-                        QueryResult::Value(_) => true,
-                        _ => false,
-                    }
+                .filter(|partial| {
+                    matches!(partial.cursor.state(), QueryResult::Value(_))
+                })
+                .map(|partial| {
+                    (partial.pos, partial.word, partial.letters_used)
                 })
                 .collect();
 
@@ -291,7 +372,7 @@ mod test {
     use super::*;
 
     fn game() -> LettersBoxedGame {
-        LettersBoxedGame::new(&["LCV", "RWA", "ENG", "TIO"]).unwrap()
+        LettersBoxedGame::new(&["LCV", "RWA", "ENG", "TIO"], None).unwrap()
     }
 
     #[test]
@@ -303,13 +384,16 @@ mod test {
     // TODO: add more tests
 }
 
+/// Usage: `letter-boxed [VOCAB_CACHE]`, where the optional path is used to
+/// cache the vocabulary between runs.
 fn main() -> Result<(), Box<dyn Error>> {
     // let game = LettersBoxedGame::new(&["TCP", "YIR", "DHA", "ONL"]).unwrap(); // 1/22
     // let game = LettersBoxedGame::new(&["LCV", "RWA", "ENG", "TIO"]).unwrap(); // 1/23
     // let game = LettersBoxedGame::new(&["CRM", "KBL", "AUH", "ISF"]).unwrap(); // 1/24
     // let game = LettersBoxedGame::new(&["NLA", "IGC", "RUP", "QKO"])?; // 1/25
 
-    let game = LettersBoxedGame::today()?;
+    let vocab_cache = std::env::args_os().nth(1).map(PathBuf::from);
+    let game = LettersBoxedGame::today(vocab_cache.as_deref())?;
 
     println!("{:?}", game.letters);
 
@@ -0,0 +1,101 @@
+//! Compares the memory use and speed of `PrefixTree` and `RadixPrefixTree`
+//! on the shared word list.
+//!
+//! Run with `cargo run --release --example radix_comparison`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use prefix_tree::{PrefixTree, QueryResult, RadixPrefixTree};
+
+const WORD_LIST: &str = include_str!("../../word_list.txt");
+const N_REPEATS: u32 = 100;
+
+/// Keeps track of the number of bytes currently allocated on the heap.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Builds a tree, returning it along with its heap usage and build time.
+fn build<T>(
+    words: &[&str],
+    empty: fn() -> T,
+    insert: impl Fn(&mut T, &str),
+) -> (T, usize, Duration) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let start = Instant::now();
+
+    let mut tree = empty();
+    for word in words {
+        insert(&mut tree, word);
+    }
+
+    let elapsed = start.elapsed();
+    let bytes = ALLOCATED.load(Ordering::Relaxed) - before;
+
+    (tree, bytes, elapsed)
+}
+
+/// Time taken to query every prefix of every word `N_REPEATS` times.
+fn time_lookups(words: &[&str], found: impl Fn(&str) -> bool) -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..N_REPEATS {
+        for word in words {
+            for (i, _) in word.char_indices() {
+                assert!(found(&word[..i]));
+            }
+            assert!(found(word));
+        }
+    }
+
+    start.elapsed()
+}
+
+fn main() {
+    let words = WORD_LIST.lines().map(str::trim).collect::<Vec<_>>();
+
+    let (trie, trie_bytes, trie_build) =
+        build(&words, PrefixTree::empty, |t, w| t.set(w.chars(), w.len()));
+    let (radix, radix_bytes, radix_build) =
+        build(&words, RadixPrefixTree::empty, |t, w| {
+            t.set(w.chars(), w.len())
+        });
+
+    let trie_lookup = time_lookups(&words, |w| {
+        !matches!(trie.get(w.chars()), QueryResult::NotFound)
+    });
+    let radix_lookup = time_lookups(&words, |w| {
+        !matches!(radix.get(w.chars()), QueryResult::NotFound)
+    });
+
+    println!("{} words", words.len());
+    println!(
+        "{:<16}{:>12}{:>14}{:>14}",
+        "", "heap bytes", "build", "lookups"
+    );
+    println!(
+        "{:<16}{:>12}{:>14?}{:>14?}",
+        "PrefixTree", trie_bytes, trie_build, trie_lookup
+    );
+    println!(
+        "{:<16}{:>12}{:>14?}{:>14?}",
+        "RadixPrefixTree", radix_bytes, radix_build, radix_lookup
+    );
+}
@@ -0,0 +1,86 @@
+use crate::{Node, PrefixTree, QueryResult};
+
+/// A position inside a [`PrefixTree`].
+///
+/// Stepping a cursor by one key only looks at the children of the current
+/// node, so search algorithms that grow a key one component at a time can
+/// carry a cursor instead of re-querying the whole key from the root.
+pub struct Cursor<'a, K: Eq, V> {
+    node: &'a Node<K, V>,
+}
+
+// manual impls so that `K` and `V` don't need to be `Clone`
+impl<K: Eq, V> Clone for Cursor<'_, K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K: Eq, V> Copy for Cursor<'_, K, V> {}
+
+impl<'a, K: Ord, V> Cursor<'a, K, V> {
+    /// Moves one key further down the tree. Returns `None` if no stored key
+    /// continues this way.
+    pub fn step(&self, key: &K) -> Option<Self> {
+        self.node.child(key).map(|node| Self { node })
+    }
+
+    /// What querying the key leading to this cursor would have returned.
+    ///
+    /// Never `QueryResult::NotFound`, since cursors only exist on stored paths.
+    pub fn state(&self) -> QueryResult<&'a V> {
+        match &self.node.value {
+            Some(value) => QueryResult::Value(value),
+            None => QueryResult::Prefix,
+        }
+    }
+}
+
+impl<K: Eq, V> PrefixTree<K, V> {
+    /// A cursor positioned at the root (i.e. at the empty key).
+    pub fn cursor(&self) -> Cursor<'_, K, V> {
+        Cursor { node: &self.0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_matches_get() {
+        let mut trie: PrefixTree<char, usize> = PrefixTree::empty();
+        trie.set("car".chars(), 1);
+        trie.set("cart".chars(), 2);
+
+        let mut cursor = trie.cursor();
+        let mut key = String::new();
+
+        for c in "cart".chars() {
+            cursor = cursor.step(&c).expect("Should be a stored prefix.");
+            key.push(c);
+
+            assert_eq!(cursor.state(), trie.get(key.chars()));
+        }
+
+        assert!(cursor.step(&'s').is_none());
+        assert!(trie.cursor().step(&'x').is_none());
+    }
+
+    #[test]
+    fn test_cursor_is_copy() {
+        let mut trie: PrefixTree<char, String> = PrefixTree::empty();
+        trie.set("ab".chars(), "ab".to_string());
+        trie.set("ac".chars(), "ac".to_string());
+
+        let a = trie.cursor().step(&'a').unwrap();
+
+        // branching from the same cursor twice
+        let ab = a.step(&'b').unwrap();
+        let ac = a.step(&'c').unwrap();
+
+        assert_eq!(a.state(), QueryResult::Prefix);
+        assert_eq!(ab.state(), QueryResult::Value(&"ab".to_string()));
+        assert_eq!(ac.state(), QueryResult::Value(&"ac".to_string()));
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+/// A minimal directed acyclic word graph.
+///
+/// Answers the same queries as [`StringPrefixTree`](crate::StringPrefixTree),
+/// but words that end the same way share their suffix nodes, so large word
+/// lists take up far less memory. Built once from a sorted word list with
+/// [`DawgBuilder`] and immutable afterwards.
+pub struct Dawg {
+    states: Vec<State>,
+    root: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+struct State {
+    is_final: bool,
+    /// INVARIANT sorted by character
+    edges: Vec<(char, usize)>,
+}
+
+impl State {
+    fn next(&self, c: char) -> Option<usize> {
+        self.edges
+            .binary_search_by_key(&c, |(edge, _)| *edge)
+            .ok()
+            .map(|i| self.edges[i].1)
+    }
+}
+
+impl Dawg {
+    /// Builds a DAWG from words in sorted order. Duplicates are ignored.
+    pub fn from_sorted<T>(words: T) -> Result<Self, &'static str>
+    where
+        T: IntoIterator,
+        T::Item: AsRef<str>,
+    {
+        let mut builder = DawgBuilder::new();
+
+        for word in words {
+            builder.insert(word.as_ref())?;
+        }
+
+        Ok(builder.build())
+    }
+
+    /// Follows `key` from the root, returning the state it ends at.
+    fn walk(&self, key: &str) -> Option<&State> {
+        let mut current = &self.states[self.root];
+        for c in key.chars() {
+            current = &self.states[current.next(c)?];
+        }
+        Some(current)
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.walk(key).is_some_and(|state| state.is_final)
+    }
+
+    pub fn contains_prefix(&self, key: &str) -> bool {
+        self.walk(key).is_some()
+    }
+
+    /// Number of states in the graph.
+    pub fn node_count(&self) -> usize {
+        self.states.len()
+    }
+}
+
+/// Incrementally builds a minimal [`Dawg`] from words inserted in sorted
+/// order.
+///
+/// Only the path of the most recently inserted word is kept unminimized.
+/// Whenever a new word diverges from that path, the nodes below the point of
+/// divergence can never change again, so they are merged with an equivalent
+/// registered state (or registered themselves).
+#[derive(Default)]
+pub struct DawgBuilder {
+    /// minimized states
+    states: Vec<State>,
+    /// minimized state -> its index in `states`
+    register: HashMap<State, usize>,
+    /// unminimized states along the path of the previous word, starting at the
+    /// root. The edge from `path[i]` to `path[i + 1]` is `path_chars[i]`.
+    path: Vec<State>,
+    path_chars: Vec<char>,
+    previous: String,
+}
+
+impl DawgBuilder {
+    pub fn new() -> Self {
+        Self {
+            path: vec![State::default()],
+            ..Default::default()
+        }
+    }
+
+    pub fn insert(&mut self, word: &str) -> Result<(), &'static str> {
+        if word < self.previous.as_str() {
+            return Err("Words must be inserted in sorted order.");
+        }
+
+        let common_prefix_len = word
+            .chars()
+            .zip(self.previous.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        self.minimize(common_prefix_len);
+
+        for c in word.chars().skip(common_prefix_len) {
+            self.path_chars.push(c);
+            self.path.push(State::default());
+        }
+
+        self.path.last_mut().unwrap().is_final = true;
+
+        self.previous.clear();
+        self.previous.push_str(word);
+
+        Ok(())
+    }
+
+    pub fn build(mut self) -> Dawg {
+        self.minimize(0);
+
+        let root = self.path.pop().unwrap();
+        let root = self.register(root);
+
+        Dawg {
+            states: self.states,
+            root,
+        }
+    }
+
+    /// Minimizes the unchecked path until only `depth` edges are left on it.
+    fn minimize(&mut self, depth: usize) {
+        while self.path_chars.len() > depth {
+            let state = self.path.pop().unwrap();
+            let c = self.path_chars.pop().unwrap();
+
+            let index = self.register(state);
+
+            // characters are inserted in order, so this keeps edges sorted
+            self.path.last_mut().unwrap().edges.push((c, index));
+        }
+    }
+
+    /// Returns the index of an equivalent minimized state, adding `state` to
+    /// the register if there isn't one yet.
+    fn register(&mut self, state: State) -> usize {
+        if let Some(&index) = self.register.get(&state) {
+            return index;
+        }
+
+        self.states.push(state.clone());
+        let index = self.states.len() - 1;
+        self.register.insert(state, index);
+
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use quickcheck_macros::quickcheck;
+
+    use super::*;
+    use crate::StringPrefixTree;
+
+    const WORD_LIST: &str = include_str!("../../word_list.txt");
+
+    #[test]
+    fn test_dawg() {
+        let dawg = Dawg::from_sorted(["car", "cart", "cat", "dog"]).unwrap();
+
+        assert!(dawg.contains("car"));
+        assert!(dawg.contains("cart"));
+        assert!(dawg.contains("cat"));
+        assert!(dawg.contains("dog"));
+
+        assert!(!dawg.contains(""));
+        assert!(!dawg.contains("ca"));
+        assert!(!dawg.contains("carts"));
+
+        assert!(dawg.contains_prefix(""));
+        assert!(dawg.contains_prefix("ca"));
+        assert!(dawg.contains_prefix("do"));
+        assert!(!dawg.contains_prefix("cow"));
+    }
+
+    #[test]
+    fn test_shares_suffixes() {
+        let dawg = Dawg::from_sorted(["tap", "taps", "top", "tops"]).unwrap();
+
+        // root -t-> _ -a,o-> _ -p-> (final) -s-> (final)
+        assert_eq!(dawg.node_count(), 5);
+    }
+
+    #[test]
+    fn test_unsorted_input() {
+        assert!(Dawg::from_sorted(["dog", "cat"]).is_err());
+        assert!(Dawg::from_sorted(["cat", "cat", "dog"]).is_ok());
+    }
+
+    #[test]
+    fn test_word_list() {
+        let words = WORD_LIST.lines().collect::<BTreeSet<_>>();
+        let dawg = Dawg::from_sorted(&words).unwrap();
+
+        let mut trie = StringPrefixTree::empty();
+        for word in &words {
+            trie.add(word);
+        }
+
+        for word in &words {
+            for (i, _) in word.char_indices() {
+                assert!(dawg.contains_prefix(&word[..i]));
+                assert_eq!(
+                    dawg.contains(&word[..i]),
+                    trie.contains(&word[..i])
+                );
+            }
+            assert!(dawg.contains(word));
+        }
+    }
+
+    /// the DAWG should behave exactly like the prefix tree
+    #[quickcheck]
+    fn test_parity_with_string_prefix_tree(
+        words: BTreeSet<String>,
+        queries: Vec<String>,
+    ) {
+        let dawg = Dawg::from_sorted(&words).unwrap();
+
+        let mut trie = StringPrefixTree::empty();
+        for word in &words {
+            trie.add(word);
+        }
+
+        let prefixes = words.iter().flat_map(|word| {
+            word.char_indices().map(|(i, _)| word[..i].to_string())
+        });
+
+        for query in prefixes.chain(queries) {
+            assert_eq!(dawg.contains(&query), trie.contains(&query));
+            assert_eq!(
+                dawg.contains_prefix(&query),
+                trie.contains_prefix(&query)
+            );
+        }
+    }
+}
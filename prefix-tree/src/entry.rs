@@ -0,0 +1,167 @@
+use crate::{Node, PrefixTree};
+
+/// A view into a single key of a [`PrefixTree`], which may or may not have a
+/// value yet. Returned by [`PrefixTree::entry`].
+pub enum Entry<'a, K: Eq, V> {
+    Occupied(OccupiedEntry<'a, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+pub struct OccupiedEntry<'a, V> {
+    value: &'a mut V,
+}
+
+/// Nodes for the key are only created once a value is inserted, so dropping
+/// a vacant entry leaves the tree unchanged.
+pub struct VacantEntry<'a, K: Eq, V> {
+    /// deepest existing node along the key
+    node: &'a mut Node<K, V>,
+    /// part of the key below `node`
+    rest: Vec<K>,
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut V
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Modifies the value in place if there is one.
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, V> OccupiedEntry<'a, V> {
+    pub fn get(&self) -> &V {
+        self.value
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self.value
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        self.value
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.value, value)
+    }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+    pub fn insert(self, value: V) -> &'a mut V {
+        let mut node = self.node;
+
+        for k in self.rest {
+            let i = node.insert_child(Node::new(Some(k), None));
+            node = &mut node.children[i];
+        }
+
+        node.value.insert(value)
+    }
+}
+
+impl<K: Ord, V> PrefixTree<K, V> {
+    /// Gets the entry for `key` for in-place manipulation.
+    pub fn entry<T>(&mut self, key: T) -> Entry<'_, K, V>
+    where
+        T: IntoIterator<Item = K>,
+    {
+        let mut node = &mut self.0;
+        let mut key = key.into_iter();
+
+        while let Some(next) = key.next() {
+            match node.search_child(&next) {
+                Ok(i) => node = &mut node.children[i],
+                Err(_) => {
+                    let rest = std::iter::once(next).chain(key).collect();
+                    return Entry::Vacant(VacantEntry { node, rest });
+                }
+            }
+        }
+
+        if node.value.is_none() {
+            let rest = Vec::new();
+            return Entry::Vacant(VacantEntry { node, rest });
+        }
+
+        let value = node.value.as_mut().unwrap();
+        Entry::Occupied(OccupiedEntry { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QueryResult;
+
+    #[test]
+    fn test_count_words() {
+        let mut counts: PrefixTree<char, usize> = PrefixTree::empty();
+
+        for word in ["car", "cat", "car", "cart", "car"] {
+            counts
+                .entry(word.chars())
+                .and_modify(|n| *n += 1)
+                .or_insert(1);
+        }
+
+        assert_eq!(counts.get("car".chars()), QueryResult::Value(&3));
+        assert_eq!(counts.get("cat".chars()), QueryResult::Value(&1));
+        assert_eq!(counts.get("cart".chars()), QueryResult::Value(&1));
+    }
+
+    #[test]
+    fn test_vacant_entry_on_existing_prefix() {
+        let mut trie: PrefixTree<char, Vec<usize>> = PrefixTree::empty();
+        trie.set("cart".chars(), vec![]);
+
+        trie.entry("car".chars()).or_default().push(1);
+        trie.entry("car".chars()).or_default().push(2);
+
+        assert_eq!(trie.get("car".chars()), QueryResult::Value(&vec![1, 2]));
+        assert_eq!(trie.get("cart".chars()), QueryResult::Value(&vec![]));
+    }
+
+    #[test]
+    fn test_unused_vacant_entry() {
+        let mut trie: PrefixTree<char, usize> = PrefixTree::empty();
+        trie.set("cat".chars(), 1);
+
+        assert!(matches!(trie.entry("dog".chars()), Entry::Vacant(_)));
+        assert!(matches!(trie.entry("ca".chars()), Entry::Vacant(_)));
+
+        // looking at an entry without inserting doesn't create any nodes
+        assert_eq!(trie.get("d".chars()), QueryResult::NotFound);
+
+        if let Entry::Occupied(mut entry) = trie.entry("cat".chars()) {
+            assert_eq!(entry.insert(5), 1);
+            assert_eq!(entry.get(), &5);
+        }
+    }
+}
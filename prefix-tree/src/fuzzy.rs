@@ -0,0 +1,164 @@
+use crate::{Node, PrefixTree};
+
+impl<K: Ord + Clone, V> Node<K, V> {
+    /// `row` holds the edit distances between the key of this node and every
+    /// prefix of `query`, i.e. one row of the Levenshtein matrix.
+    fn collect_fuzzy<'a>(
+        &'a self,
+        query: &[K],
+        max_edits: usize,
+        row: &[usize],
+        path: &mut Vec<K>,
+        matches: &mut Vec<(Vec<K>, &'a V, usize)>,
+    ) {
+        if let (Some(value), Some(&distance)) = (&self.value, row.last()) {
+            if distance <= max_edits {
+                matches.push((path.clone(), value, distance));
+            }
+        }
+
+        for (k, child) in Self::keyed(&self.children) {
+            let mut next_row = Vec::with_capacity(row.len());
+            next_row.push(row[0] + 1);
+
+            for (j, q) in query.iter().enumerate() {
+                let insertion = next_row[j] + 1;
+                let deletion = row[j + 1] + 1;
+                let substitution = row[j] + usize::from(q != k);
+
+                next_row.push(insertion.min(deletion).min(substitution));
+            }
+
+            // distances along a row can only grow deeper in the tree, so
+            // stop once every prefix of the query is too far away
+            if next_row.iter().min().is_some_and(|&d| d <= max_edits) {
+                path.push(k.clone());
+                child.collect_fuzzy(query, max_edits, &next_row, path, matches);
+                path.pop();
+            }
+        }
+    }
+}
+
+impl<K: Ord + Clone, V> PrefixTree<K, V> {
+    /// All stored `(key, value, distance)` triples whose key is within
+    /// `max_edits` insertions, deletions or substitutions of `query`.
+    ///
+    /// Sorted by distance, then by key.
+    pub fn fuzzy<T>(
+        &self,
+        query: T,
+        max_edits: usize,
+    ) -> Vec<(Vec<K>, &V, usize)>
+    where
+        T: IntoIterator<Item = K>,
+    {
+        let query = query.into_iter().collect::<Vec<_>>();
+        let first_row = (0..=query.len()).collect::<Vec<_>>();
+        let mut matches = Vec::new();
+
+        self.0.collect_fuzzy(
+            &query,
+            max_edits,
+            &first_row,
+            &mut Vec::new(),
+            &mut matches,
+        );
+
+        matches.sort_by(|(a, _, a_dist), (b, _, b_dist)| {
+            a_dist.cmp(b_dist).then_with(|| a.cmp(b))
+        });
+
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+
+    use super::*;
+
+    /// textbook dynamic programming edit distance
+    fn levenshtein(a: &[u8], b: &[u8]) -> usize {
+        let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+        for (i, x) in a.iter().enumerate() {
+            let mut next_row = vec![i + 1];
+            for (j, y) in b.iter().enumerate() {
+                next_row.push(
+                    (next_row[j] + 1)
+                        .min(row[j + 1] + 1)
+                        .min(row[j] + usize::from(x != y)),
+                );
+            }
+            row = next_row;
+        }
+
+        row[b.len()]
+    }
+
+    #[test]
+    fn test_fuzzy() {
+        let mut trie: PrefixTree<char, usize> = PrefixTree::empty();
+        for (i, word) in ["cat", "cart", "cast", "dog", ""].iter().enumerate() {
+            trie.set(word.chars(), i);
+        }
+
+        let words = |query: &str, max_edits| {
+            trie.fuzzy(query.chars(), max_edits)
+                .into_iter()
+                .map(|(key, _, d)| (key.into_iter().collect::<String>(), d))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(words("cat", 0), vec![("cat".to_string(), 0)]);
+        assert_eq!(
+            words("cat", 1),
+            vec![
+                ("cat".to_string(), 0),
+                ("cart".to_string(), 1),
+                ("cast".to_string(), 1)
+            ]
+        );
+        assert_eq!(words("", 0), vec![("".to_string(), 0)]);
+        assert_eq!(words("do", 1), vec![("dog".to_string(), 1)]);
+    }
+
+    /// walking the trie should find exactly what a brute force scan finds
+    #[quickcheck]
+    fn test_fuzzy_matches_brute_force(
+        words: Vec<Vec<u8>>,
+        query: Vec<u8>,
+        max_edits: u8,
+    ) {
+        // keep the alphabet small so that there are plenty of near misses
+        let shrink = |word: &Vec<u8>| word.iter().map(|b| b % 4).collect();
+        let words = words.iter().map(shrink).collect::<Vec<Vec<u8>>>();
+        let query: Vec<u8> = shrink(&query);
+        let max_edits = usize::from(max_edits % 4);
+
+        let mut trie = PrefixTree::empty();
+        for word in &words {
+            trie.set(word.clone(), ());
+        }
+
+        let mut expected = words
+            .iter()
+            .map(|word| (word.clone(), levenshtein(word, &query)))
+            .filter(|(_, d)| *d <= max_edits)
+            .collect::<Vec<_>>();
+        expected.sort_by(|(a, a_dist), (b, b_dist)| {
+            a_dist.cmp(b_dist).then_with(|| a.cmp(b))
+        });
+        expected.dedup();
+
+        let found = trie
+            .fuzzy(query, max_edits)
+            .into_iter()
+            .map(|(key, _, d)| (key, d))
+            .collect::<Vec<_>>();
+
+        assert_eq!(found, expected);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{Node, PrefixTree};
+
+/// Iterator over the `(key, value)` pairs stored in a [`PrefixTree`].
+///
+/// Keys are yielded in lexicographic order, regardless of the order in which
+/// they were inserted.
+pub struct Iter<'a, K: Eq, V> {
+    /// nodes left to visit, along with the length of their key
+    stack: Vec<(&'a Node<K, V>, usize)>,
+    /// key of the most recently visited node
+    path: Vec<K>,
+}
+
+impl<'a, K: Ord + Clone, V> Iter<'a, K, V> {
+    fn new(start: Option<&'a Node<K, V>>, path: Vec<K>) -> Self {
+        Self {
+            stack: start.map(|node| (node, path.len())).into_iter().collect(),
+            path,
+        }
+    }
+}
+
+impl<'a, K: Ord + Clone, V> Iterator for Iter<'a, K, V> {
+    type Item = (Vec<K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node, depth)) = self.stack.pop() {
+            // the root has no key component of its own
+            if let Some(k) = &node.prefix_component {
+                self.path.truncate(depth - 1);
+                self.path.push(k.clone());
+            }
+
+            // push in reverse so the smallest child is visited first
+            self.stack.extend(
+                node.children.iter().rev().map(|child| (child, depth + 1)),
+            );
+
+            if let Some(value) = &node.value {
+                return Some((self.path.clone(), value));
+            }
+        }
+
+        // exhausted tree
+        None
+    }
+}
+
+impl<K: Ord + Clone, V> PrefixTree<K, V> {
+    /// Iterates over every stored `(key, value)` pair in lexicographic order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter::new(Some(&self.0), Vec::new())
+    }
+
+    /// Iterates over every stored key that begins with `prefix` (including
+    /// `prefix` itself), in lexicographic order.
+    pub fn iter_prefix<T>(&self, prefix: T) -> Iter<'_, K, V>
+    where
+        T: IntoIterator<Item = K>,
+    {
+        let prefix = prefix.into_iter().collect::<Vec<_>>();
+        let start = self.0.descend(prefix.iter().cloned());
+
+        Iter::new(start, prefix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iter_is_sorted() {
+        let mut trie: PrefixTree<char, usize> = PrefixTree::empty();
+        let words = ["zebra", "car", "", "cart", "apple", "ca"];
+
+        for (i, word) in words.iter().enumerate() {
+            trie.set(word.chars(), i);
+        }
+
+        let stored = trie
+            .iter()
+            .map(|(key, &value)| (key.into_iter().collect::<String>(), value))
+            .collect::<Vec<_>>();
+
+        let mut expected = words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| (word.to_string(), i))
+            .collect::<Vec<_>>();
+        expected.sort();
+
+        assert_eq!(stored, expected);
+    }
+
+    #[test]
+    fn test_iter_insertion_order_independent() {
+        let words = ["dog", "do", "cat", "door", "cab"];
+
+        let mut forwards: PrefixTree<char, ()> = PrefixTree::empty();
+        let mut backwards: PrefixTree<char, ()> = PrefixTree::empty();
+
+        for word in words {
+            forwards.set(word.chars(), ());
+        }
+        for word in words.iter().rev() {
+            backwards.set(word.chars(), ());
+        }
+
+        assert!(forwards.iter().eq(backwards.iter()));
+    }
+
+    /// every way of adding and removing keys keeps the children sorted
+    #[test]
+    fn test_iter_sorted_after_edits() {
+        let mut trie: PrefixTree<char, ()> = ["dog", "cat", "dot", "ant"]
+            .iter()
+            .map(|w| (w.chars(), ()))
+            .collect();
+        trie.remove("ant".chars());
+        trie.entry("bee".chars()).or_insert(());
+        trie.entry("dig".chars()).or_insert(());
+        let other = ["eel", "cab", "do"].iter().map(|w| (w.chars(), ()));
+        let trie = trie.union(other.collect(), |(), ()| ());
+
+        let stored = trie
+            .iter()
+            .map(|(key, _)| key.into_iter().collect::<String>())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            stored,
+            vec!["bee", "cab", "cat", "dig", "do", "dog", "dot", "eel"]
+        );
+    }
+
+    #[test]
+    fn test_iter_prefix() {
+        let mut trie: PrefixTree<char, ()> = PrefixTree::empty();
+
+        for word in ["do", "dog", "door", "cat"] {
+            trie.set(word.chars(), ());
+        }
+
+        let completions = |prefix: &str| {
+            trie.iter_prefix(prefix.chars())
+                .map(|(key, _)| key.into_iter().collect::<String>())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(completions("do"), vec!["do", "dog", "door"]);
+        assert_eq!(completions("doo"), vec!["door"]);
+        assert_eq!(completions("c"), vec!["cat"]);
+        assert_eq!(completions("x"), Vec::<String>::new());
+        assert_eq!(completions("").len(), 4);
+    }
+}
@@ -1,3 +1,31 @@
+mod cursor;
+mod dawg;
+mod entry;
+mod fuzzy;
+mod iter;
+mod multiset;
+mod pattern;
+mod prefixes;
+mod radix;
+mod set_ops;
+#[cfg(feature = "serde")]
+mod snapshot;
+mod stats;
+
+pub use cursor::Cursor;
+pub use dawg::{Dawg, DawgBuilder};
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+pub use iter::Iter;
+pub use multiset::LetterBag;
+pub use pattern::Slot;
+pub use prefixes::Prefixes;
+pub use radix::RadixPrefixTree;
+pub use stats::Stats;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PrefixTree<K: Eq, V>(Node<K, V>);
 
 /// The result of querying a prefix tree
@@ -15,7 +43,9 @@ impl<K: Eq, V> PrefixTree<K, V> {
     pub fn empty() -> Self {
         Self(Node::new(None, None))
     }
+}
 
+impl<K: Ord, V> PrefixTree<K, V> {
     pub fn get<T>(&self, key: T) -> QueryResult<&V>
     where
         T: IntoIterator<Item = K>,
@@ -23,6 +53,24 @@ impl<K: Eq, V> PrefixTree<K, V> {
         self.0.get(key.into_iter())
     }
 
+    pub fn get_mut<T>(&mut self, key: T) -> QueryResult<&mut V>
+    where
+        T: IntoIterator<Item = K>,
+    {
+        self.0.get_mut(key.into_iter())
+    }
+
+    /// Removes a key from the tree, returning its value if it was present.
+    ///
+    /// Nodes that no longer lead to any stored value are pruned, so the
+    /// removed key stops being reported as a `QueryResult::Prefix`.
+    pub fn remove<T>(&mut self, key: T) -> Option<V>
+    where
+        T: IntoIterator<Item = K>,
+    {
+        self.0.remove(key.into_iter())
+    }
+
     pub fn set<T>(&mut self, key: T, value: V)
     where
         T: IntoIterator<Item = K>,
@@ -31,6 +79,29 @@ impl<K: Eq, V> PrefixTree<K, V> {
     }
 }
 
+impl<K: Ord, V, T> FromIterator<(T, V)> for PrefixTree<K, V>
+where
+    T: IntoIterator<Item = K>,
+{
+    fn from_iter<I: IntoIterator<Item = (T, V)>>(iter: I) -> Self {
+        let mut tree = Self::empty();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<K: Ord, V, T> Extend<(T, V)> for PrefixTree<K, V>
+where
+    T: IntoIterator<Item = K>,
+{
+    fn extend<I: IntoIterator<Item = (T, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.set(key, value);
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 struct Node<K, V>
 where
     K: Eq,
@@ -38,6 +109,7 @@ where
     /// None only at the root
     prefix_component: Option<K>,
     value: Option<V>,
+    /// INVARIANT sorted by key component
     children: Vec<Node<K, V>>,
 }
 
@@ -50,43 +122,53 @@ impl<K: Eq, V> Node<K, V> {
         }
     }
 
-    fn set<T>(&mut self, mut key: T, value: V)
+    /// `children` along with their key components, which only the root lacks.
+    fn keyed(children: &[Self]) -> impl Iterator<Item = (&K, &Self)> {
+        children.iter().filter_map(|child| {
+            child.prefix_component.as_ref().map(|k| (k, child))
+        })
+    }
+
+    /// A node that stores no value and has no children is a dead branch.
+    fn is_empty(&self) -> bool {
+        self.value.is_none() && self.children.is_empty()
+    }
+}
+
+impl<K: Ord, V> Node<K, V> {
+    /// Where the child for `key` is, or where it would have to be inserted to
+    /// keep the children sorted.
+    pub(crate) fn search_child(&self, key: &K) -> Result<usize, usize> {
+        self.children.binary_search_by(|child| {
+            child.prefix_component.as_ref().cmp(&Some(key))
+        })
+    }
+
+    fn child(&self, key: &K) -> Option<&Self> {
+        self.search_child(key).ok().map(|i| &self.children[i])
+    }
+
+    fn child_index(&self, key: &K) -> Option<usize> {
+        self.search_child(key).ok()
+    }
+
+    /// Follows `key` down from this node, returning the node it ends at.
+    fn descend<T>(&self, key: T) -> Option<&Self>
     where
         T: Iterator<Item = K>,
     {
-        match key.next() {
-            None => {
-                // store value at this node
-                self.value = Some(value)
-            }
-            Some(next) => {
-                // descend tree, if possible
-                let possible_child = self
-                    .children
-                    .iter_mut()
-                    .filter(|child| match &child.prefix_component {
-                        Some(value) => value == &next,
-                        _ => false,
-                    })
-                    .next();
-
-                match possible_child {
-                    None => {
-                        let mut new_child = Self::new(Some(next), None);
-                        new_child.set(key, value);
-                        self.children.push(new_child);
-                    }
-                    Some(child) => child.set(key, value),
-                }
-            }
+        let mut current = self;
+        for next in key {
+            current = current.child(&next)?;
         }
+        Some(current)
     }
 
     fn get<T>(&self, mut key: T) -> QueryResult<&V>
     where
         T: Iterator<Item = K>,
     {
-        return match key.next() {
+        match key.next() {
             None => {
                 // return value stored at this node, if it exists
                 match &self.value {
@@ -96,47 +178,235 @@ impl<K: Eq, V> Node<K, V> {
             }
             Some(next) => {
                 // descend tree, if possible
-                match self
-                    .children
-                    .iter()
-                    .filter(|child| match &child.prefix_component {
-                        Some(value) => value == &next,
-                        _ => false,
-                    })
-                    .next()
-                {
+                match self.child(&next) {
                     Some(child) => child.get(key),
                     None => QueryResult::NotFound,
                 }
             }
+        }
+    }
+
+    fn get_mut<T>(&mut self, mut key: T) -> QueryResult<&mut V>
+    where
+        T: Iterator<Item = K>,
+    {
+        match key.next() {
+            None => match &mut self.value {
+                Some(value) => QueryResult::Value(value),
+                None => QueryResult::Prefix,
+            },
+            Some(next) => match self.child_index(&next) {
+                Some(i) => self.children[i].get_mut(key),
+                None => QueryResult::NotFound,
+            },
+        }
+    }
+
+    fn remove<T>(&mut self, mut key: T) -> Option<V>
+    where
+        T: Iterator<Item = K>,
+    {
+        match key.next() {
+            None => self.value.take(),
+            Some(next) => {
+                let i = self.child_index(&next)?;
+                let removed = self.children[i].remove(key);
+
+                // prune the child if nothing is stored beneath it anymore
+                if self.children[i].is_empty() {
+                    self.children.remove(i);
+                }
+
+                removed
+            }
+        }
+    }
+
+    /// Inserts `child` in key order, returning its index.
+    pub(crate) fn insert_child(&mut self, child: Self) -> usize {
+        let i = match &child.prefix_component {
+            Some(k) => self.search_child(k).unwrap_or_else(|i| i),
+            None => 0,
         };
+        self.children.insert(i, child);
+        i
+    }
+
+    fn set<T>(&mut self, mut key: T, value: V)
+    where
+        T: Iterator<Item = K>,
+    {
+        match key.next() {
+            None => {
+                // store value at this node
+                self.value = Some(value)
+            }
+            Some(next) => {
+                // descend tree, creating the child if needed
+                let i = match self.search_child(&next) {
+                    Ok(i) => i,
+                    Err(i) => {
+                        self.children.insert(i, Self::new(Some(next), None));
+                        i
+                    }
+                };
+                self.children[i].set(key, value)
+            }
+        }
+    }
+
+    /// Checks that every child list is sorted without duplicates and that
+    /// only the root lacks a key component, which lookups rely on.
+    #[cfg(feature = "serde")]
+    pub(crate) fn check_order(&self) -> Result<(), &'static str> {
+        if self.prefix_component.is_some() {
+            return Err("The root has a key component.");
+        }
+
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            let mut previous = None;
+            for child in &node.children {
+                let Some(k) = &child.prefix_component else {
+                    return Err("A child has no key component.");
+                };
+                if previous.is_some_and(|previous| previous >= k) {
+                    return Err("Children are not sorted.");
+                }
+                previous = Some(k);
+                stack.push(child);
+            }
+        }
+
+        Ok(())
     }
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct StringPrefixTree(PrefixTree<char, ()>);
 
 impl StringPrefixTree {
+    /// Matches any single character in [`StringPrefixTree::matches`].
+    pub const WILDCARD: char = '?';
+
     pub fn empty() -> Self {
         Self(PrefixTree::empty())
     }
 
     pub fn contains(&self, key: &str) -> bool {
-        match self.0.get(key.chars()) {
-            QueryResult::Value(()) => true,
-            _ => false,
-        }
+        matches!(self.0.get(key.chars()), QueryResult::Value(()))
     }
 
     pub fn contains_prefix(&self, key: &str) -> bool {
-        match self.0.get(key.chars()) {
-            QueryResult::NotFound => false,
-            _ => true,
-        }
+        !matches!(self.0.get(key.chars()), QueryResult::NotFound)
     }
 
     pub fn add(&mut self, key: &str) {
         self.0.set(key.chars(), ())
     }
+
+    /// All stored words, in lexicographic order.
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        self.0.iter().map(|(key, ())| key.into_iter().collect())
+    }
+
+    /// All stored words beginning with `prefix`, in lexicographic order.
+    pub fn iter_prefix(
+        &self,
+        prefix: &str,
+    ) -> impl Iterator<Item = String> + '_ {
+        self.0
+            .iter_prefix(prefix.chars())
+            .map(|(key, ())| key.into_iter().collect())
+    }
+
+    /// All stored words within `max_edits` insertions, deletions or
+    /// substitutions of `query`, closest first.
+    pub fn fuzzy(&self, query: &str, max_edits: usize) -> Vec<(String, usize)> {
+        self.0
+            .fuzzy(query.chars(), max_edits)
+            .into_iter()
+            .map(|(key, (), distance)| (key.into_iter().collect(), distance))
+            .collect()
+    }
+
+    /// All stored words that can be spelled using the letters in `bag`, in
+    /// lexicographic order.
+    pub fn constructible(&self, bag: &LetterBag<char>) -> Vec<String> {
+        self.0
+            .constructible(bag)
+            .into_iter()
+            .map(|(key, ())| key.into_iter().collect())
+            .collect()
+    }
+
+    /// Every stored word that is a prefix of `key` (including `key` itself),
+    /// shortest first.
+    pub fn prefixes_of<'a, 'k: 'a>(
+        &'a self,
+        key: &'k str,
+    ) -> impl Iterator<Item = &'k str> + 'a {
+        self.0.prefixes_of(key.chars()).map(|(prefix, ())| {
+            &key[..prefix.iter().map(|c| c.len_utf8()).sum::<usize>()]
+        })
+    }
+
+    /// The longest stored word that is a prefix of `key`.
+    pub fn longest_prefix<'a>(&self, key: &'a str) -> Option<&'a str> {
+        let (prefix, ()) = self.0.longest_prefix(key.chars())?;
+        Some(&key[..prefix.iter().map(|c| c.len_utf8()).sum::<usize>()])
+    }
+
+    /// All stored words matching `pattern`, where [`Self::WILDCARD`] stands
+    /// for any single character (e.g. `"G?M?A"`), in lexicographic order.
+    pub fn matches(&self, pattern: &str) -> Vec<String> {
+        let pattern = pattern.chars().map(|c| match c {
+            Self::WILDCARD => Slot::Any,
+            c => Slot::Exact(c),
+        });
+
+        self.0
+            .matches(pattern)
+            .into_iter()
+            .map(|(key, ())| key.into_iter().collect())
+            .collect()
+    }
+
+    /// All words in either tree.
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0.union(other.0, |(), ()| ()))
+    }
+
+    /// All words in both trees.
+    pub fn intersection(self, other: &Self) -> Self {
+        Self(self.0.intersection(&other.0))
+    }
+
+    /// All words in this tree but not in `other`.
+    pub fn difference(self, other: &Self) -> Self {
+        Self(self.0.difference(&other.0))
+    }
+
+    /// Removes a word, returning whether it was present.
+    pub fn remove(&mut self, key: &str) -> bool {
+        self.0.remove(key.chars()).is_some()
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for StringPrefixTree {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut tree = Self::empty();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for StringPrefixTree {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for key in iter {
+            self.add(key.as_ref());
+        }
+    }
 }
 
 #[cfg(test)]
@@ -183,4 +453,224 @@ mod tests {
         assert!(!trie.contains_prefix("woooorld"));
         assert!(!trie.contains_prefix("asdlfa"));
     }
+
+    #[test]
+    fn test_remove() {
+        let mut trie: PrefixTree<char, usize> = PrefixTree::empty();
+
+        trie.set("car".chars(), 1);
+        trie.set("cart".chars(), 2);
+        trie.set("cat".chars(), 3);
+
+        assert_eq!(trie.remove("cart".chars()), Some(2));
+        assert_eq!(trie.remove("cart".chars()), None);
+        assert_eq!(trie.remove("ca".chars()), None);
+        assert_eq!(trie.remove("dog".chars()), None);
+
+        assert_eq!(trie.get("cart".chars()), QueryResult::NotFound);
+        assert_eq!(trie.get("car".chars()), QueryResult::Value(&1));
+        assert_eq!(trie.get("cat".chars()), QueryResult::Value(&3));
+
+        // removing a key that is still a prefix keeps its descendants
+        trie.set("cart".chars(), 2);
+        assert_eq!(trie.remove("car".chars()), Some(1));
+        assert_eq!(trie.get("car".chars()), QueryResult::Prefix);
+        assert_eq!(trie.get("cart".chars()), QueryResult::Value(&2));
+    }
+
+    #[test]
+    fn test_remove_prunes_dead_branches() {
+        let mut trie: PrefixTree<char, ()> = PrefixTree::empty();
+
+        trie.set("hello".chars(), ());
+        trie.set("help".chars(), ());
+
+        trie.remove("hello".chars());
+        assert_eq!(trie.get("hell".chars()), QueryResult::NotFound);
+        assert_eq!(trie.get("hel".chars()), QueryResult::Prefix);
+
+        trie.remove("help".chars());
+        assert_eq!(trie.get("h".chars()), QueryResult::NotFound);
+        assert!(trie.0.children.is_empty());
+    }
+
+    #[test]
+    fn test_string_prefix_tree_remove() {
+        let mut trie = StringPrefixTree::empty();
+
+        trie.add("hello");
+        trie.add("help");
+
+        assert!(trie.remove("hello"));
+        assert!(!trie.remove("hello"));
+        assert!(!trie.remove("hel"));
+
+        assert!(!trie.contains("hello"));
+        assert!(!trie.contains_prefix("hell"));
+        assert!(trie.contains("help"));
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut trie: PrefixTree<char, usize> = PrefixTree::empty();
+        trie.set("cat".chars(), 1);
+
+        if let QueryResult::Value(value) = trie.get_mut("cat".chars()) {
+            *value += 10;
+        }
+
+        assert_eq!(trie.get("cat".chars()), QueryResult::Value(&11));
+        assert_eq!(trie.get_mut("ca".chars()), QueryResult::Prefix);
+        assert_eq!(trie.get_mut("dog".chars()), QueryResult::NotFound);
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let trie: PrefixTree<char, usize> =
+            [("cat".chars(), 1), ("car".chars(), 2)]
+                .into_iter()
+                .collect();
+
+        assert_eq!(trie.get("cat".chars()), QueryResult::Value(&1));
+        assert_eq!(trie.get("car".chars()), QueryResult::Value(&2));
+
+        let mut words: StringPrefixTree = ["cat", "car"].into_iter().collect();
+        words.extend(vec!["dog".to_string()]);
+
+        assert!(words.contains("car"));
+        assert!(words.contains("dog"));
+        assert!(!words.contains("ca"));
+    }
+
+    #[test]
+    fn test_string_prefix_tree_iter() {
+        let mut trie = StringPrefixTree::empty();
+
+        for word in ["carp", "cat", "car", "dog", "cart"] {
+            trie.add(word);
+        }
+
+        assert_eq!(
+            trie.iter().collect::<Vec<_>>(),
+            vec!["car", "carp", "cart", "cat", "dog"]
+        );
+        assert_eq!(
+            trie.iter_prefix("car").collect::<Vec<_>>(),
+            vec!["car", "carp", "cart"]
+        );
+        assert_eq!(trie.iter_prefix("cow").count(), 0);
+    }
+
+    #[test]
+    fn test_string_prefix_tree_matches() {
+        let mut trie = StringPrefixTree::empty();
+
+        for word in ["gamma", "gumbo", "gemma", "game", "aloof"] {
+            trie.add(word);
+        }
+
+        assert_eq!(trie.matches("g?m?a"), vec!["gamma", "gemma"]);
+        assert_eq!(trie.matches("?????").len(), 4);
+        assert_eq!(trie.matches("ga?e"), vec!["game"]);
+        assert!(trie.matches("g?m").is_empty());
+        assert!(trie.matches("").is_empty());
+    }
+
+    #[test]
+    fn test_string_prefix_tree_fuzzy() {
+        let mut trie = StringPrefixTree::empty();
+
+        for word in ["gamma", "gamut", "game", "aloof", "slant"] {
+            trie.add(word);
+        }
+
+        assert_eq!(trie.fuzzy("gamma", 0), vec![("gamma".to_string(), 0)]);
+        assert_eq!(
+            trie.fuzzy("gamm", 1),
+            vec![("game".to_string(), 1), ("gamma".to_string(), 1)]
+        );
+        assert_eq!(trie.fuzzy("alof", 1), vec![("aloof".to_string(), 1)]);
+        assert!(trie.fuzzy("zzzzz", 2).is_empty());
+    }
+
+    #[test]
+    fn test_string_prefix_tree_segmentation() {
+        let words: StringPrefixTree =
+            ["hot", "hotdog", "dog", "dogs", "stand", "tan", "and"]
+                .into_iter()
+                .collect();
+
+        /// split `s` into stored words, preferring longer words first
+        fn segment<'a>(
+            words: &StringPrefixTree,
+            s: &'a str,
+        ) -> Option<Vec<&'a str>> {
+            if s.is_empty() {
+                return Some(vec![]);
+            }
+
+            let prefixes = words.prefixes_of(s).collect::<Vec<_>>();
+            prefixes.into_iter().rev().find_map(|prefix| {
+                let mut rest = segment(words, &s[prefix.len()..])?;
+                rest.insert(0, prefix);
+                Some(rest)
+            })
+        }
+
+        assert_eq!(
+            segment(&words, "hotdogstand"),
+            Some(vec!["hotdog", "stand"])
+        );
+        assert_eq!(
+            segment(&words, "hotdogsand"),
+            Some(vec!["hot", "dogs", "and"])
+        );
+        assert_eq!(
+            segment(&words, "hotdogstan"),
+            Some(vec!["hot", "dogs", "tan"])
+        );
+        assert_eq!(segment(&words, "hotdo"), None);
+
+        assert_eq!(words.longest_prefix("hotdogs"), Some("hotdog"));
+        assert_eq!(words.longest_prefix("ho"), None);
+    }
+
+    #[test]
+    fn test_string_prefix_tree_set_ops() {
+        let base: StringPrefixTree =
+            ["cat", "car", "dog"].into_iter().collect();
+        let extras: StringPrefixTree = ["cart", "dog"].into_iter().collect();
+        let blocklist: StringPrefixTree = ["car"].into_iter().collect();
+
+        let vocab = base.union(extras).difference(&blocklist);
+        assert_eq!(
+            vocab.iter().collect::<Vec<_>>(),
+            vec!["cart", "cat", "dog"]
+        );
+
+        let animals: StringPrefixTree =
+            ["cat", "dog", "emu"].into_iter().collect();
+        let vocab = vocab.intersection(&animals);
+        assert_eq!(vocab.iter().collect::<Vec<_>>(), vec!["cat", "dog"]);
+    }
+
+    #[test]
+    fn test_string_prefix_tree_constructible() {
+        let mut trie = StringPrefixTree::empty();
+
+        for word in ["tan", "ant", "tent", "neat", "eaten", "at"] {
+            trie.add(word);
+        }
+
+        let bag = LetterBag::new("tane".chars());
+        assert_eq!(trie.constructible(&bag), vec!["ant", "at", "neat", "tan"]);
+
+        let bag = LetterBag::new("tane".chars()).with_reuse();
+        assert_eq!(trie.constructible(&bag).len(), 6);
+
+        let bag = LetterBag::new("tane".chars())
+            .with_reuse()
+            .with_required('e');
+        assert_eq!(trie.constructible(&bag), vec!["eaten", "neat", "tent"]);
+    }
 }
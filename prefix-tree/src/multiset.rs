@@ -0,0 +1,161 @@
+use crate::{Node, PrefixTree};
+
+/// A multiset of keys to build words out of, e.g. the letters of a Spelling
+/// Bee or an anagram.
+#[derive(Debug, Clone)]
+pub struct LetterBag<K> {
+    /// each distinct key and how many times it can be used
+    counts: Vec<(K, usize)>,
+    /// whether keys can be used any number of times
+    reuse: bool,
+    /// a key that every word must use at least once
+    required: Option<K>,
+}
+
+impl<K: Eq> LetterBag<K> {
+    pub fn new<T>(keys: T) -> Self
+    where
+        T: IntoIterator<Item = K>,
+    {
+        let mut counts: Vec<(K, usize)> = Vec::new();
+
+        for key in keys {
+            match counts.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((key, 1)),
+            }
+        }
+
+        Self {
+            counts,
+            reuse: false,
+            required: None,
+        }
+    }
+
+    /// Allows every key in the bag to be used any number of times.
+    pub fn with_reuse(mut self) -> Self {
+        self.reuse = true;
+        self
+    }
+
+    /// Only allows words that use `key` at least once. `key` must also be in
+    /// the bag to be usable.
+    pub fn with_required(mut self, key: K) -> Self {
+        self.required = Some(key);
+        self
+    }
+}
+
+impl<K: Ord + Clone, V> Node<K, V> {
+    /// `remaining` counts how many more times each key of `bag` can be used
+    fn collect_constructible<'a>(
+        &'a self,
+        bag: &LetterBag<K>,
+        remaining: &mut [usize],
+        has_required: bool,
+        path: &mut Vec<K>,
+        matches: &mut Vec<(Vec<K>, &'a V)>,
+    ) {
+        if let Some(value) = &self.value {
+            if has_required || bag.required.is_none() {
+                matches.push((path.clone(), value));
+            }
+        }
+
+        for (k, child) in Self::keyed(&self.children) {
+            // prune as soon as the bag runs out of this key
+            let Some(i) = bag.counts.iter().position(|(key, _)| key == k)
+            else {
+                continue;
+            };
+            if !bag.reuse && remaining[i] == 0 {
+                continue;
+            }
+
+            remaining[i] -= usize::from(!bag.reuse);
+            path.push(k.clone());
+
+            child.collect_constructible(
+                bag,
+                remaining,
+                has_required || bag.required.as_ref() == Some(k),
+                path,
+                matches,
+            );
+
+            path.pop();
+            remaining[i] += usize::from(!bag.reuse);
+        }
+    }
+}
+
+impl<K: Ord + Clone, V> PrefixTree<K, V> {
+    /// All stored `(key, value)` pairs whose key can be built from the keys
+    /// in `bag`, in lexicographic order.
+    pub fn constructible(&self, bag: &LetterBag<K>) -> Vec<(Vec<K>, &V)> {
+        let mut remaining = bag
+            .counts
+            .iter()
+            .map(|(_, count)| *count)
+            .collect::<Vec<_>>();
+        let mut matches = Vec::new();
+
+        self.0.collect_constructible(
+            bag,
+            &mut remaining,
+            false,
+            &mut Vec::new(),
+            &mut matches,
+        );
+
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(
+        trie: &PrefixTree<char, ()>,
+        bag: &LetterBag<char>,
+    ) -> Vec<String> {
+        trie.constructible(bag)
+            .into_iter()
+            .map(|(key, _)| key.into_iter().collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_respects_counts() {
+        let mut trie = PrefixTree::empty();
+        for word in ["a", "aa", "aaa", "ab", "ba", "abc"] {
+            trie.set(word.chars(), ());
+        }
+
+        let bag = LetterBag::new("aab".chars());
+        assert_eq!(words(&trie, &bag), vec!["a", "aa", "ab", "ba"]);
+
+        let bag = LetterBag::new("ab".chars()).with_reuse();
+        assert_eq!(words(&trie, &bag), vec!["a", "aa", "aaa", "ab", "ba"]);
+
+        let bag = LetterBag::new("".chars());
+        assert!(words(&trie, &bag).is_empty());
+    }
+
+    #[test]
+    fn test_required_key() {
+        let mut trie = PrefixTree::empty();
+        for word in ["a", "ab", "ba", "bb"] {
+            trie.set(word.chars(), ());
+        }
+
+        let bag = LetterBag::new("ab".chars()).with_reuse().with_required('b');
+        assert_eq!(words(&trie, &bag), vec!["ab", "ba", "bb"]);
+
+        // the required key isn't in the bag, so nothing can use it
+        let bag = LetterBag::new("a".chars()).with_required('b');
+        assert!(words(&trie, &bag).is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{Node, PrefixTree};
+
+/// A single position in a pattern query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Slot<K> {
+    /// Matches exactly this key.
+    Exact(K),
+    /// Matches any key.
+    Any,
+    /// Matches any one of these keys.
+    OneOf(Vec<K>),
+}
+
+impl<K: Eq> Slot<K> {
+    pub fn matches(&self, key: &K) -> bool {
+        match self {
+            Slot::Exact(k) => k == key,
+            Slot::Any => true,
+            Slot::OneOf(keys) => keys.contains(key),
+        }
+    }
+}
+
+impl<K: Ord + Clone, V> Node<K, V> {
+    fn collect_matches<'a>(
+        &'a self,
+        pattern: &[Slot<K>],
+        path: &mut Vec<K>,
+        matches: &mut Vec<(Vec<K>, &'a V)>,
+    ) {
+        let Some((slot, rest)) = pattern.split_first() else {
+            // end of pattern. keep this node if it stores a value
+            if let Some(value) = &self.value {
+                matches.push((path.clone(), value));
+            }
+            return;
+        };
+
+        // no need to scan the children when only one of them can match
+        let children = match slot {
+            Slot::Exact(k) => match self.search_child(k) {
+                Ok(i) => &self.children[i..=i],
+                Err(_) => &[],
+            },
+            _ => &self.children[..],
+        };
+
+        for (k, child) in Self::keyed(children) {
+            if slot.matches(k) {
+                path.push(k.clone());
+                child.collect_matches(rest, path, matches);
+                path.pop();
+            }
+        }
+    }
+}
+
+impl<K: Ord + Clone, V> PrefixTree<K, V> {
+    /// All stored `(key, value)` pairs matching `pattern` position by
+    /// position, in lexicographic order. Only keys with exactly as many
+    /// components as the pattern can match.
+    pub fn matches<T>(&self, pattern: T) -> Vec<(Vec<K>, &V)>
+    where
+        T: IntoIterator<Item = Slot<K>>,
+    {
+        let pattern = pattern.into_iter().collect::<Vec<_>>();
+        let mut matches = Vec::new();
+
+        self.0
+            .collect_matches(&pattern, &mut Vec::new(), &mut matches);
+
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trie(words: &[&str]) -> PrefixTree<char, usize> {
+        let mut trie = PrefixTree::empty();
+        for (i, word) in words.iter().enumerate() {
+            trie.set(word.chars(), i);
+        }
+        trie
+    }
+
+    fn words(matches: Vec<(Vec<char>, &usize)>) -> Vec<String> {
+        matches
+            .into_iter()
+            .map(|(key, _)| key.into_iter().collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_exact_and_wildcards() {
+        let trie = trie(&["cat", "cot", "cut", "cart", "dog"]);
+
+        let pattern = vec![Slot::Exact('c'), Slot::Any, Slot::Exact('t')];
+        assert_eq!(words(trie.matches(pattern)), vec!["cat", "cot", "cut"]);
+
+        let pattern = vec![Slot::Any; 4];
+        assert_eq!(words(trie.matches(pattern)), vec!["cart"]);
+
+        let pattern = vec![Slot::Any; 2];
+        assert!(trie.matches(pattern).is_empty());
+    }
+
+    #[test]
+    fn test_one_of() {
+        let trie = trie(&["cat", "cot", "cut", "cart", "dog"]);
+
+        let pattern = vec![
+            Slot::OneOf(vec!['c', 'd']),
+            Slot::OneOf(vec!['o', 'u']),
+            Slot::Any,
+        ];
+        assert_eq!(words(trie.matches(pattern)), vec!["cot", "cut", "dog"]);
+    }
+
+    #[test]
+    fn test_matches_values() {
+        let trie = trie(&["ab", "ac"]);
+
+        let matches = trie.matches(vec![Slot::Exact('a'), Slot::Any]);
+        assert_eq!(matches, vec![(vec!['a', 'b'], &0), (vec!['a', 'c'], &1)]);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{Node, PrefixTree};
+
+/// Iterator over the stored keys along the path of a key, shortest first.
+/// Returned by [`PrefixTree::prefixes_of`].
+pub struct Prefixes<'a, K: Eq, V, I> {
+    /// next node on the path, `None` once the path leaves the tree
+    current: Option<&'a Node<K, V>>,
+    /// rest of the key below `current`
+    key: I,
+    /// key of `current`
+    path: Vec<K>,
+}
+
+impl<'a, K, V, I> Iterator for Prefixes<'a, K, V, I>
+where
+    K: Ord + Clone,
+    I: Iterator<Item = K>,
+{
+    type Item = (Vec<K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = self.current?;
+            let found = node.value.as_ref().map(|v| (self.path.clone(), v));
+
+            // step before returning, so the next call picks up below `node`
+            self.current = self.key.next().and_then(|k| {
+                let child = node.child(&k)?;
+                self.path.push(k);
+                Some(child)
+            });
+
+            if found.is_some() {
+                return found;
+            }
+        }
+    }
+}
+
+impl<K: Ord + Clone, V> PrefixTree<K, V> {
+    /// Every stored `(key, value)` pair whose key is a prefix of `key`
+    /// (including `key` itself), shortest first.
+    pub fn prefixes_of<T>(&self, key: T) -> Prefixes<'_, K, V, T::IntoIter>
+    where
+        T: IntoIterator<Item = K>,
+    {
+        Prefixes {
+            current: Some(&self.0),
+            key: key.into_iter(),
+            path: Vec::new(),
+        }
+    }
+
+    /// The stored `(key, value)` pair with the longest key that is a prefix
+    /// of `key`.
+    pub fn longest_prefix<T>(&self, key: T) -> Option<(Vec<K>, &V)>
+    where
+        T: IntoIterator<Item = K>,
+    {
+        self.prefixes_of(key).last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefixes_of() {
+        let trie: PrefixTree<char, usize> =
+            [("a".chars(), 1), ("abc".chars(), 3), ("abcde".chars(), 5)]
+                .into_iter()
+                .collect();
+
+        let prefixes = trie
+            .prefixes_of("abcdx".chars())
+            .map(|(key, &value)| (key.into_iter().collect::<String>(), value))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            prefixes,
+            vec![("a".to_string(), 1), ("abc".to_string(), 3)]
+        );
+        assert_eq!(trie.prefixes_of("xyz".chars()).count(), 0);
+    }
+
+    #[test]
+    fn test_longest_prefix() {
+        let mut trie: PrefixTree<char, usize> = PrefixTree::empty();
+        trie.set("".chars(), 0);
+        trie.set("ab".chars(), 2);
+
+        assert_eq!(
+            trie.longest_prefix("abc".chars()),
+            Some((vec!['a', 'b'], &2))
+        );
+        assert_eq!(
+            trie.longest_prefix("ab".chars()),
+            Some((vec!['a', 'b'], &2))
+        );
+        assert_eq!(trie.longest_prefix("a".chars()), Some((vec![], &0)));
+
+        trie.remove("".chars());
+        assert_eq!(trie.longest_prefix("a".chars()), None);
+    }
+}
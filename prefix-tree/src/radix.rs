@@ -0,0 +1,198 @@
+use crate::QueryResult;
+
+/// A path-compressed version of [`PrefixTree`](crate::PrefixTree).
+///
+/// Chains of nodes with a single child are merged into one node holding a
+/// run of keys, which saves a lot of nodes on long words.
+pub struct RadixPrefixTree<K: Eq, V>(RadixNode<K, V>);
+
+impl<K: Eq, V> RadixPrefixTree<K, V> {
+    pub fn empty() -> Self {
+        Self(RadixNode::new(Vec::new(), None))
+    }
+
+    pub fn get<T>(&self, key: T) -> QueryResult<&V>
+    where
+        T: IntoIterator<Item = K>,
+    {
+        self.0.get(key.into_iter())
+    }
+
+    pub fn set<T>(&mut self, key: T, value: V)
+    where
+        T: IntoIterator<Item = K>,
+    {
+        self.0.set(key.into_iter().collect(), value)
+    }
+}
+
+struct RadixNode<K, V>
+where
+    K: Eq,
+{
+    /// Empty only at the root
+    label: Vec<K>,
+    value: Option<V>,
+    children: Vec<RadixNode<K, V>>,
+}
+
+impl<K: Eq, V> RadixNode<K, V> {
+    fn new(label: Vec<K>, value: Option<V>) -> Self {
+        Self {
+            label,
+            value,
+            children: Vec::new(),
+        }
+    }
+
+    /// INVARIANT: children start with distinct keys
+    fn child_index(&self, key: &K) -> Option<usize> {
+        self.children
+            .iter()
+            .position(|child| child.label.first() == Some(key))
+    }
+
+    /// `key` is relative to the end of this node's label
+    fn set(&mut self, mut key: Vec<K>, value: V) {
+        let Some(first) = key.first() else {
+            // store value at this node
+            self.value = Some(value);
+            return;
+        };
+
+        let Some(i) = self.child_index(first) else {
+            // nothing shares a prefix with the key, so it gets its own leaf
+            self.children.push(Self::new(key, Some(value)));
+            return;
+        };
+
+        let child = &mut self.children[i];
+        let common = child
+            .label
+            .iter()
+            .zip(&key)
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        if common < child.label.len() {
+            // the key diverges partway through the child's label, so split
+            // the child in two at the point of divergence
+            let mut tail = Self::new(child.label.split_off(common), None);
+            tail.value = child.value.take();
+            tail.children = std::mem::take(&mut child.children);
+
+            child.children.push(tail);
+        }
+
+        child.set(key.split_off(common), value)
+    }
+
+    fn get<T>(&self, mut key: T) -> QueryResult<&V>
+    where
+        T: Iterator<Item = K>,
+    {
+        let mut current = self;
+
+        loop {
+            // the whole label of `current` has been matched
+            let Some(next) = key.next() else {
+                return match &current.value {
+                    Some(value) => QueryResult::Value(value),
+                    None => QueryResult::Prefix,
+                };
+            };
+
+            let Some(i) = current.child_index(&next) else {
+                return QueryResult::NotFound;
+            };
+            current = &current.children[i];
+
+            for expected in &current.label[1..] {
+                match key.next() {
+                    // key ends in the middle of a label
+                    None => return QueryResult::Prefix,
+                    Some(k) if k == *expected => {}
+                    Some(_) => return QueryResult::NotFound,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+
+    use super::*;
+    use crate::PrefixTree;
+
+    #[test]
+    fn test_radix_tree() {
+        let mut trie: RadixPrefixTree<char, usize> = RadixPrefixTree::empty();
+
+        trie.set("carpet".chars(), 1);
+        trie.set("car".chars(), 2);
+        trie.set("cart".chars(), 3);
+
+        assert_eq!(trie.get("carpet".chars()), QueryResult::Value(&1));
+        assert_eq!(trie.get("car".chars()), QueryResult::Value(&2));
+        assert_eq!(trie.get("cart".chars()), QueryResult::Value(&3));
+
+        assert_eq!(trie.get("".chars()), QueryResult::Prefix);
+        assert_eq!(trie.get("ca".chars()), QueryResult::Prefix);
+        assert_eq!(trie.get("carp".chars()), QueryResult::Prefix);
+
+        assert_eq!(trie.get("cat".chars()), QueryResult::NotFound);
+        assert_eq!(trie.get("carpets".chars()), QueryResult::NotFound);
+        assert_eq!(trie.get("dog".chars()), QueryResult::NotFound);
+    }
+
+    #[test]
+    fn test_radix_tree_compresses_paths() {
+        let mut trie: RadixPrefixTree<char, ()> = RadixPrefixTree::empty();
+
+        trie.set("antidisestablishmentarianism".chars(), ());
+        assert_eq!(trie.0.children.len(), 1);
+        assert!(trie.0.children[0].children.is_empty());
+
+        // splits the existing node in two
+        trie.set("antibody".chars(), ());
+        let anti = &trie.0.children[0];
+        assert_eq!(anti.label, "anti".chars().collect::<Vec<_>>());
+        assert_eq!(anti.children.len(), 2);
+    }
+
+    #[test]
+    fn test_radix_tree_overwrite() {
+        let mut trie: RadixPrefixTree<char, usize> = RadixPrefixTree::empty();
+
+        trie.set("hello".chars(), 1);
+        trie.set("hello".chars(), 2);
+
+        assert_eq!(trie.get("hello".chars()), QueryResult::Value(&2));
+    }
+
+    /// the radix tree should behave exactly like the uncompressed one
+    #[quickcheck]
+    fn test_parity_with_prefix_tree(
+        entries: Vec<(Vec<u8>, u8)>,
+        queries: Vec<Vec<u8>>,
+    ) {
+        let mut trie = PrefixTree::empty();
+        let mut radix = RadixPrefixTree::empty();
+
+        for (key, value) in &entries {
+            trie.set(key.clone(), *value);
+            radix.set(key.clone(), *value);
+        }
+
+        // every prefix of every stored key, plus some arbitrary keys
+        let prefixes = entries
+            .iter()
+            .flat_map(|(key, _)| (0..=key.len()).map(|i| key[..i].to_vec()));
+
+        for query in prefixes.chain(queries) {
+            assert_eq!(trie.get(query.clone()), radix.get(query));
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{Node, PrefixTree};
+
+impl<K: Ord, V> Node<K, V> {
+    fn union_with<F>(&mut self, other: Self, merge: &mut F)
+    where
+        F: FnMut(V, V) -> V,
+    {
+        self.value = match (self.value.take(), other.value) {
+            (Some(a), Some(b)) => Some(merge(a, b)),
+            (a, b) => a.or(b),
+        };
+
+        for other_child in other.children {
+            let shared = other_child
+                .prefix_component
+                .as_ref()
+                .and_then(|k| self.search_child(k).ok());
+
+            match shared {
+                Some(i) => self.children[i].union_with(other_child, merge),
+                // only in `other`, so the whole subtree can be moved over
+                None => {
+                    self.insert_child(other_child);
+                }
+            }
+        }
+    }
+
+    fn intersect_with<W>(&mut self, other: &Node<K, W>) {
+        if other.value.is_none() {
+            self.value = None;
+        }
+
+        self.children.retain_mut(|child| {
+            let other_child =
+                child.prefix_component.as_ref().and_then(|k| other.child(k));
+
+            match other_child {
+                Some(other_child) => {
+                    child.intersect_with(other_child);
+                    !child.is_empty()
+                }
+                // only in `self`, so nothing below here is shared
+                None => false,
+            }
+        });
+    }
+
+    fn subtract<W>(&mut self, other: &Node<K, W>) {
+        if other.value.is_some() {
+            self.value = None;
+        }
+
+        self.children.retain_mut(|child| {
+            let other_child =
+                child.prefix_component.as_ref().and_then(|k| other.child(k));
+
+            match other_child {
+                Some(other_child) => {
+                    child.subtract(other_child);
+                    !child.is_empty()
+                }
+                // only in `self`, so nothing below here is removed
+                None => true,
+            }
+        });
+    }
+}
+
+/// Set operations, done by walking both trees at the same time. Subtrees
+/// that only one of the trees has are moved or dropped whole.
+impl<K: Ord, V> PrefixTree<K, V> {
+    /// All keys in either tree. Values of keys in both trees are combined
+    /// with `merge(self_value, other_value)`.
+    pub fn union<F>(mut self, other: Self, mut merge: F) -> Self
+    where
+        F: FnMut(V, V) -> V,
+    {
+        self.0.union_with(other.0, &mut merge);
+        self
+    }
+
+    /// All keys in both trees, keeping the values from `self`.
+    pub fn intersection<W>(mut self, other: &PrefixTree<K, W>) -> Self {
+        self.0.intersect_with(&other.0);
+        self
+    }
+
+    /// All keys in `self` but not in `other`.
+    pub fn difference<W>(mut self, other: &PrefixTree<K, W>) -> Self {
+        self.0.subtract(&other.0);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use quickcheck_macros::quickcheck;
+
+    use super::*;
+    use crate::QueryResult;
+
+    fn tree(map: &BTreeMap<Vec<u8>, u8>) -> PrefixTree<u8, u32> {
+        map.iter()
+            .map(|(k, v)| (k.clone(), u32::from(*v)))
+            .collect()
+    }
+
+    fn contents(tree: &PrefixTree<u8, u32>) -> Vec<(Vec<u8>, u32)> {
+        tree.iter().map(|(k, v)| (k, *v)).collect()
+    }
+
+    #[test]
+    fn test_union_merges_values() {
+        let a: PrefixTree<char, u32> = [("car".chars(), 1), ("cat".chars(), 2)]
+            .into_iter()
+            .collect();
+        let b: PrefixTree<char, u32> =
+            [("car".chars(), 10), ("dog".chars(), 20)]
+                .into_iter()
+                .collect();
+
+        let union = a.union(b, |x, y| x + y);
+
+        assert_eq!(union.get("car".chars()), QueryResult::Value(&11));
+        assert_eq!(union.get("cat".chars()), QueryResult::Value(&2));
+        assert_eq!(union.get("dog".chars()), QueryResult::Value(&20));
+    }
+
+    #[test]
+    fn test_results_are_pruned() {
+        let a: PrefixTree<char, ()> =
+            [("cart".chars(), ()), ("dog".chars(), ())]
+                .into_iter()
+                .collect();
+        let b: PrefixTree<char, ()> =
+            [("car".chars(), ()), ("dog".chars(), ())]
+                .into_iter()
+                .collect();
+
+        let intersection = a.intersection(&b);
+        assert_eq!(intersection.get("ca".chars()), QueryResult::NotFound);
+        assert_eq!(intersection.get("dog".chars()), QueryResult::Value(&()));
+
+        let difference = intersection.difference(&b);
+        assert_eq!(difference.get("d".chars()), QueryResult::NotFound);
+    }
+
+    /// set operations should agree with the same operations on maps
+    #[quickcheck]
+    fn test_matches_maps(a: BTreeMap<Vec<u8>, u8>, b: BTreeMap<Vec<u8>, u8>) {
+        let mut union = a
+            .iter()
+            .map(|(k, v)| (k.clone(), u32::from(*v)))
+            .collect::<BTreeMap<_, _>>();
+        for (k, v) in &b {
+            *union.entry(k.clone()).or_default() += u32::from(*v);
+        }
+        let union = union.into_iter().collect::<Vec<_>>();
+
+        let intersection = a
+            .iter()
+            .filter(|(k, _)| b.contains_key(*k))
+            .map(|(k, v)| (k.clone(), u32::from(*v)))
+            .collect::<Vec<_>>();
+
+        let difference = a
+            .iter()
+            .filter(|(k, _)| !b.contains_key(*k))
+            .map(|(k, v)| (k.clone(), u32::from(*v)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(contents(&tree(&a).union(tree(&b), |x, y| x + y)), union);
+        assert_eq!(contents(&tree(&a).intersection(&tree(&b))), intersection);
+        assert_eq!(contents(&tree(&a).difference(&tree(&b))), difference);
+    }
+}
@@ -0,0 +1,169 @@
+//! Compact binary snapshots of prefix trees, so that a dictionary can be
+//! built once and loaded quickly afterwards.
+//!
+//! A snapshot is a short header (magic bytes followed by a little-endian
+//! format version) and then the bincode encoding of the tree.
+
+use std::error::Error;
+use std::io::{Read, Write};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{PrefixTree, StringPrefixTree};
+
+const MAGIC: &[u8; 4] = b"PFXT";
+
+/// Bump whenever the layout of the tree changes.
+const VERSION: u32 = 2;
+
+fn write_header<W: Write>(writer: &mut W) -> Result<(), Box<dyn Error>> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    Ok(())
+}
+
+fn read_header<R: Read>(reader: &mut R) -> Result<(), Box<dyn Error>> {
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err("Not a prefix tree snapshot.".into());
+    }
+
+    let mut version = [0; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version != VERSION {
+        return Err(format!("Unsupported snapshot version {version}.").into());
+    }
+
+    Ok(())
+}
+
+impl<K, V> PrefixTree<K, V>
+where
+    K: Eq + Serialize,
+    V: Serialize,
+{
+    pub fn write_snapshot<W: Write>(
+        &self,
+        mut writer: W,
+    ) -> Result<(), Box<dyn Error>> {
+        write_header(&mut writer)?;
+        bincode::serialize_into(writer, self)?;
+        Ok(())
+    }
+}
+
+impl<K, V> PrefixTree<K, V>
+where
+    K: Ord + DeserializeOwned,
+    V: DeserializeOwned,
+{
+    pub fn read_snapshot<R: Read>(
+        mut reader: R,
+    ) -> Result<Self, Box<dyn Error>> {
+        read_header(&mut reader)?;
+
+        let tree: Self = bincode::deserialize_from(reader)?;
+        tree.0.check_order()?;
+
+        Ok(tree)
+    }
+}
+
+impl StringPrefixTree {
+    pub fn write_snapshot<W: Write>(
+        &self,
+        writer: W,
+    ) -> Result<(), Box<dyn Error>> {
+        self.0.write_snapshot(writer)
+    }
+
+    pub fn read_snapshot<R: Read>(reader: R) -> Result<Self, Box<dyn Error>> {
+        PrefixTree::read_snapshot(reader).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QueryResult;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let trie: PrefixTree<char, usize> =
+            [("car".chars(), 1), ("cart".chars(), 2), ("dog".chars(), 3)]
+                .into_iter()
+                .collect();
+
+        let mut bytes = Vec::new();
+        trie.write_snapshot(&mut bytes).unwrap();
+
+        let loaded: PrefixTree<char, usize> =
+            PrefixTree::read_snapshot(bytes.as_slice()).unwrap();
+
+        assert!(trie.iter().eq(loaded.iter()));
+        assert_eq!(loaded.get("ca".chars()), QueryResult::Prefix);
+    }
+
+    #[test]
+    fn test_string_snapshot_round_trip() {
+        let words: StringPrefixTree = ["hello", "world"].into_iter().collect();
+
+        let mut bytes = Vec::new();
+        words.write_snapshot(&mut bytes).unwrap();
+
+        let loaded = StringPrefixTree::read_snapshot(bytes.as_slice()).unwrap();
+
+        assert!(loaded.contains("hello"));
+        assert!(loaded.contains("world"));
+        assert!(!loaded.contains("hell"));
+    }
+
+    #[test]
+    fn test_bad_header() {
+        let words: StringPrefixTree = ["hello"].into_iter().collect();
+
+        let mut bytes = Vec::new();
+        words.write_snapshot(&mut bytes).unwrap();
+
+        // wrong version
+        let mut wrong_version = bytes.clone();
+        wrong_version[4] += 1;
+        assert!(
+            StringPrefixTree::read_snapshot(wrong_version.as_slice()).is_err()
+        );
+
+        // not a snapshot at all
+        assert!(StringPrefixTree::read_snapshot(&b"hello world"[..]).is_err());
+        assert!(StringPrefixTree::read_snapshot(&b""[..]).is_err());
+    }
+
+    #[test]
+    fn test_unsorted_children() {
+        let build = || {
+            [("a".chars(), 1), ("b".chars(), 2)]
+                .into_iter()
+                .collect::<PrefixTree<char, usize>>()
+        };
+        let read_back = |trie: &PrefixTree<char, usize>| {
+            let mut bytes = Vec::new();
+            trie.write_snapshot(&mut bytes).unwrap();
+            PrefixTree::<char, usize>::read_snapshot(bytes.as_slice())
+        };
+        assert!(read_back(&build()).is_ok());
+
+        let mut swapped = build();
+        swapped.0.children.swap(0, 1);
+        assert!(read_back(&swapped).is_err());
+
+        let mut duplicated = build();
+        duplicated.0.children[1].prefix_component = Some('a');
+        assert!(read_back(&duplicated).is_err());
+
+        let mut rootless = build();
+        rootless.0.children[0].prefix_component = None;
+        assert!(read_back(&rootless).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt::{Debug, Write};
+
+use crate::{Node, PrefixTree, StringPrefixTree};
+
+/// The shape of a prefix tree, as reported by [`PrefixTree::stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    /// nodes in the tree, including the root
+    pub node_count: usize,
+    pub word_count: usize,
+    /// `depths[d]` is the number of nodes `d` keys below the root
+    pub depths: Vec<usize>,
+    /// mean number of children of the nodes that have any
+    pub branching_factor: f64,
+    /// memory allocated for the nodes, not counting any heap memory owned by
+    /// the keys and values themselves
+    pub heap_bytes: usize,
+}
+
+impl<K: Eq, V> PrefixTree<K, V> {
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats {
+            node_count: 0,
+            word_count: 0,
+            depths: Vec::new(),
+            branching_factor: 0.0,
+            heap_bytes: 0,
+        };
+        let (mut parents, mut edges) = (0, 0);
+
+        let mut stack = vec![(&self.0, 0)];
+        while let Some((node, depth)) = stack.pop() {
+            stats.node_count += 1;
+            if node.value.is_some() {
+                stats.word_count += 1;
+            }
+            if stats.depths.len() <= depth {
+                stats.depths.push(0);
+            }
+            stats.depths[depth] += 1;
+
+            if !node.children.is_empty() {
+                parents += 1;
+                edges += node.children.len();
+            }
+            stats.heap_bytes +=
+                node.children.capacity() * size_of::<Node<K, V>>();
+            stack.extend(node.children.iter().map(|child| (child, depth + 1)));
+        }
+
+        if parents > 0 {
+            stats.branching_factor = edges as f64 / parents as f64;
+        }
+        stats
+    }
+}
+
+impl<K: Ord + Debug, V: Debug> PrefixTree<K, V> {
+    /// The subtree of every key starting with `prefix` as a Graphviz DOT
+    /// graph, with each node labeled by its key component and value.
+    pub fn to_dot<T>(&self, prefix: T) -> Option<String>
+    where
+        T: IntoIterator<Item = K>,
+    {
+        let start = self.0.descend(prefix.into_iter())?;
+
+        let mut dot = String::from("digraph prefix_tree {\n");
+        let mut next_id = 1;
+        let mut stack = vec![(start, 0)];
+        while let Some((node, id)) = stack.pop() {
+            let mut label = match &node.prefix_component {
+                Some(k) => escape(&format!("{k:?}")),
+                None => "Root".to_string(),
+            };
+            if let Some(value) = &node.value {
+                write!(label, "\\n{}", escape(&format!("{value:?}"))).unwrap();
+            }
+            writeln!(dot, "    n{id} [label=\"{label}\"];").unwrap();
+
+            for child in &node.children {
+                writeln!(dot, "    n{id} -> n{next_id};").unwrap();
+                stack.push((child, next_id));
+                next_id += 1;
+            }
+        }
+        dot.push_str("}\n");
+
+        Some(dot)
+    }
+}
+
+impl StringPrefixTree {
+    pub fn stats(&self) -> Stats {
+        self.0.stats()
+    }
+
+    /// The subtree of every word starting with `prefix` as a Graphviz DOT
+    /// graph.
+    pub fn to_dot(&self, prefix: &str) -> Option<String> {
+        self.0.to_dot(prefix.chars())
+    }
+}
+
+/// escapes a label for a double-quoted DOT string
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let trie = StringPrefixTree::from_iter(["car", "cat", "ca", "dog"]);
+        let stats = trie.stats();
+
+        // root, c, a, r, t, d, o, g
+        assert_eq!(stats.node_count, 8);
+        assert_eq!(stats.word_count, 4);
+        assert_eq!(stats.depths, vec![1, 2, 2, 3]);
+        // root and "ca" have two children, "c", "d" and "do" one
+        assert_eq!(stats.branching_factor, 7.0 / 5.0);
+        assert!(stats.heap_bytes > 0);
+
+        let empty = StringPrefixTree::empty().stats();
+        assert_eq!(empty.node_count, 1);
+        assert_eq!(empty.word_count, 0);
+        assert_eq!(empty.heap_bytes, 0);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let trie = PrefixTree::from_iter([("ab".chars(), 1), ("a".chars(), 2)]);
+
+        assert_eq!(trie.to_dot("b".chars()), None);
+        assert_eq!(
+            trie.to_dot("".chars()).unwrap(),
+            [
+                "digraph prefix_tree {",
+                "    n0 [label=\"Root\"];",
+                "    n0 -> n1;",
+                "    n1 [label=\"'a'\\n2\"];",
+                "    n1 -> n2;",
+                "    n2 [label=\"'b'\\n1\"];",
+                "}\n",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Measures push, lookup and traversal throughput of `Trie` on the shared
+//! word list.
+//!
+//! Run with `cargo run --release --example throughput`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use vocab_tree::{Pattern, Trie};
+
+const WORD_LIST: &str = include_str!("../../word_list.txt");
+const N_REPEATS: u32 = 20;
+const N_PAIRED: usize = 300;
+
+/// Fastest of `N_REPEATS` runs of `f`, which is less noisy than the mean.
+fn time(mut f: impl FnMut()) -> Duration {
+    (0..N_REPEATS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+/// Prints the throughput of every operation on a trie of `words`.
+fn report(name: &str, words: &[&str]) {
+    let build = || {
+        let mut trie = Trie::new();
+        for (popularity, word) in words.iter().enumerate() {
+            trie.push(word.chars(), popularity);
+        }
+        trie
+    };
+    let trie = build();
+
+    let push = time(|| {
+        black_box(build());
+    });
+    let get = time(|| {
+        for word in words {
+            assert!(black_box(trie.get(word.chars())).is_some());
+        }
+    });
+    let unordered = time(|| {
+        black_box(trie.iter_values_unordered(None).count());
+    });
+    let ordered = time(|| {
+        black_box(trie.iter_values_ordered(None).count());
+    });
+    let pattern = Pattern::try_from("?a*e").unwrap();
+    let ordered_pattern = time(|| {
+        let matches = trie.iter_values_ordered(Some(pattern.clone()));
+        black_box(matches.count());
+    });
+
+    let per_second =
+        |elapsed: Duration| words.len() as f64 / elapsed.as_secs_f64();
+
+    println!("{name}: {} words, best of {N_REPEATS} runs", words.len());
+    println!("{:<24}{:>14}{:>16}", "", "time", "words / s");
+    for (name, elapsed) in [
+        ("push", push),
+        ("get", get),
+        ("unordered traversal", unordered),
+        ("ordered traversal", ordered),
+        ("ordered, \"?a*e\"", ordered_pattern),
+    ] {
+        println!("{:<24}{:>14?}{:>16.0}", name, elapsed, per_second(elapsed));
+    }
+}
+
+fn main() {
+    let words = WORD_LIST.lines().map(str::trim).collect::<Vec<_>>();
+    report("word list", &words);
+
+    // pairs of words, for a bigger trie with wider nodes further down
+    let pairs = words
+        .iter()
+        .take(N_PAIRED)
+        .flat_map(|a| {
+            words.iter().take(N_PAIRED).map(move |b| a.to_string() + b)
+        })
+        .collect::<Vec<_>>();
+    let pairs = pairs.iter().map(String::as_str).collect::<Vec<_>>();
+    println!();
+    report("word pairs", &pairs);
+}
@@ -0,0 +1,153 @@
+use std::cmp::Reverse;
+use std::ops::Add;
+
+/// How the values of all the words below a node are summarized.
+///
+/// Ordered traversals visit nodes in increasing order of their summary, so
+/// words come out sorted whenever a summary never exceeds the summaries of the
+/// nodes below it (as with [`Min`] and [`Max`]).
+///
+/// ```
+/// use vocab_tree::{Aggregate, Trie};
+///
+/// /// the longest word below each node
+/// struct Longest;
+///
+/// impl Aggregate<String> for Longest {
+///     type Summary = usize;
+///
+///     fn single(value: &String) -> usize {
+///         value.len()
+///     }
+///
+///     fn combine(a: &usize, b: &usize) -> usize {
+///         *a.max(b)
+///     }
+/// }
+///
+/// let mut trie = Trie::<_, _, Longest>::default();
+/// trie.push("ab".chars(), "ab".to_string());
+/// trie.push("abcd".chars(), "abcd".to_string());
+/// assert_eq!(trie.summary("a".chars()), Some(&4));
+/// ```
+pub trait Aggregate<V> {
+    type Summary: Clone;
+
+    /// summary of a single word
+    fn single(value: &V) -> Self::Summary;
+
+    /// summary of two disjoint sets of words
+    fn combine(a: &Self::Summary, b: &Self::Summary) -> Self::Summary;
+}
+
+/// Smallest value below a node, e.g. the lowest cost. Ordered traversals
+/// return the smallest values first.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Min;
+
+impl<V: Clone + Ord> Aggregate<V> for Min {
+    type Summary = V;
+
+    fn single(value: &V) -> V {
+        value.clone()
+    }
+
+    fn combine(a: &V, b: &V) -> V {
+        a.min(b).clone()
+    }
+}
+
+/// Largest value below a node, e.g. the highest popularity. Ordered traversals
+/// return the largest values first.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Max;
+
+impl<V: Clone + Ord> Aggregate<V> for Max {
+    type Summary = Reverse<V>;
+
+    fn single(value: &V) -> Reverse<V> {
+        Reverse(value.clone())
+    }
+
+    fn combine(a: &Reverse<V>, b: &Reverse<V>) -> Reverse<V> {
+        a.min(b).clone()
+    }
+}
+
+/// Number of words below a node.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Count;
+
+impl<V> Aggregate<V> for Count {
+    type Summary = usize;
+
+    fn single(_: &V) -> usize {
+        1
+    }
+
+    fn combine(a: &usize, b: &usize) -> usize {
+        a + b
+    }
+}
+
+/// Total of the values below a node.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sum;
+
+impl<V: Clone + Add<Output = V>> Aggregate<V> for Sum {
+    type Summary = V;
+
+    fn single(value: &V) -> V {
+        value.clone()
+    }
+
+    fn combine(a: &V, b: &V) -> V {
+        a.clone() + b.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    use super::*;
+
+    #[test]
+    fn test_max() {
+        let mut trie = Trie::<_, _, Max>::default();
+        for (word, popularity) in [("car", 3), ("cat", 9), ("dog", 5)] {
+            trie.push(word.chars(), popularity);
+        }
+
+        assert_eq!(
+            trie.iter_strings_ordered(None).collect::<Vec<_>>(),
+            vec![("cat".into(), &9), ("dog".into(), &5), ("car".into(), &3)]
+        );
+        assert_eq!(trie.summary("ca".chars()), Some(&Reverse(9)));
+    }
+
+    #[test]
+    fn test_count_and_sum() {
+        let words = [("car", 3), ("cat", 9), ("cart", 1), ("dog", 5)];
+
+        let mut counts = Trie::<_, _, Count>::default();
+        let mut sums = Trie::<_, _, Sum>::default();
+        for (word, value) in words {
+            counts.push(word.chars(), value);
+            sums.push(word.chars(), value);
+        }
+
+        assert_eq!(counts.summary("".chars()), Some(&4));
+        assert_eq!(counts.summary("car".chars()), Some(&2));
+        assert_eq!(counts.summary("cow".chars()), None);
+        assert_eq!(sums.summary("ca".chars()), Some(&13));
+        assert_eq!(sums.get("cart".chars()), Some(&1));
+
+        // pushing an existing word replaces its value
+        counts.push("car".chars(), 0);
+        sums.push("car".chars(), 0);
+        assert_eq!(counts.summary("".chars()), Some(&4));
+        assert_eq!(sums.summary("ca".chars()), Some(&10));
+        assert_eq!(sums.get("car".chars()), Some(&0));
+    }
+}
@@ -0,0 +1,160 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, VecDeque};
+
+use crate::pattern::States;
+
+/// A node waiting to be visited by a traversal.
+///
+/// Candidates are ordered by value, then by depth.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Candidate<'a, V> {
+    value: &'a V,
+    depth: usize,
+    pub(crate) index: usize,
+    pub(crate) states: States,
+}
+
+impl<'a, V> Candidate<'a, V> {
+    pub(crate) fn new(
+        value: &'a V,
+        depth: usize,
+        index: usize,
+        states: States,
+    ) -> Self {
+        Self {
+            value,
+            depth,
+            index,
+            states,
+        }
+    }
+
+    /// The aggregate value of the node, covering every word below it.
+    pub fn value(&self) -> &'a V {
+        self.value
+    }
+
+    /// Number of keys between the node and the start of the traversal.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+/// Decides the order in which a traversal visits nodes.
+pub trait Frontier<'a, V> {
+    fn push(&mut self, candidate: Candidate<'a, V>);
+
+    fn pop(&mut self) -> Option<Candidate<'a, V>>;
+
+    fn is_empty(&self) -> bool;
+}
+
+/// Depth-first: the most recently found node is visited next.
+#[derive(Debug)]
+pub struct Stack<'a, V>(Vec<Candidate<'a, V>>);
+
+impl<V> Default for Stack<'_, V> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<'a, V> Frontier<'a, V> for Stack<'a, V> {
+    fn push(&mut self, candidate: Candidate<'a, V>) {
+        self.0.push(candidate)
+    }
+
+    fn pop(&mut self) -> Option<Candidate<'a, V>> {
+        self.0.pop()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Breadth-first: nodes are visited in order of depth, so shorter words come
+/// out first.
+#[derive(Debug)]
+pub struct Queue<'a, V>(VecDeque<Candidate<'a, V>>);
+
+impl<V> Default for Queue<'_, V> {
+    fn default() -> Self {
+        Self(VecDeque::new())
+    }
+}
+
+impl<'a, V> Frontier<'a, V> for Queue<'a, V> {
+    fn push(&mut self, candidate: Candidate<'a, V>) {
+        self.0.push_back(candidate)
+    }
+
+    fn pop(&mut self) -> Option<Candidate<'a, V>> {
+        self.0.pop_front()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Best-first: the node with the smallest summary is visited next. Words come
+/// out sorted whenever a summary never exceeds the summaries of the nodes
+/// below it (as with [`Min`](crate::Min) and [`Max`](crate::Max)).
+#[derive(Debug)]
+pub struct Heap<'a, V>(BinaryHeap<Reverse<Candidate<'a, V>>>);
+
+impl<V: Ord> Default for Heap<'_, V> {
+    fn default() -> Self {
+        Self(BinaryHeap::new())
+    }
+}
+
+impl<'a, V: Ord> Frontier<'a, V> for Heap<'a, V> {
+    fn push(&mut self, candidate: Candidate<'a, V>) {
+        self.0.push(Reverse(candidate))
+    }
+
+    fn pop(&mut self) -> Option<Candidate<'a, V>> {
+        self.0.pop().map(|Reverse(candidate)| candidate)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Best-first, but only the `width` best nodes are kept around. Worse nodes
+/// are dropped for good, so the traversal is fast but may miss words.
+#[derive(Debug)]
+pub struct Beam<'a, V> {
+    candidates: BTreeSet<Candidate<'a, V>>,
+    width: usize,
+}
+
+impl<V> Beam<'_, V> {
+    pub fn new(width: usize) -> Self {
+        Self {
+            candidates: BTreeSet::new(),
+            width,
+        }
+    }
+}
+
+impl<'a, V: Ord> Frontier<'a, V> for Beam<'a, V> {
+    fn push(&mut self, candidate: Candidate<'a, V>) {
+        self.candidates.insert(candidate);
+
+        if self.candidates.len() > self.width {
+            self.candidates.pop_last();
+        }
+    }
+
+    fn pop(&mut self) -> Option<Candidate<'a, V>> {
+        self.candidates.pop_first()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.candidates.is_empty()
+    }
+}
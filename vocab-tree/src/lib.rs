@@ -1,5 +1,19 @@
+mod aggregate;
+mod frontier;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod pattern;
+mod stats;
 mod traversal;
 mod traversal_inorder;
 mod trie;
 
+#[cfg(feature = "serde")]
+mod snapshot;
+
+pub use aggregate::{Aggregate, Count, Max, Min, Sum};
+pub use frontier::{Beam, Candidate, Frontier, Heap, Queue, Stack};
+pub use pattern::Pattern;
+pub use stats::Stats;
+pub use traversal_inorder::Budgeted;
 pub use trie::Trie;
@@ -0,0 +1,111 @@
+use rayon::prelude::*;
+
+use crate::aggregate::Aggregate;
+use crate::pattern::{Pattern, States};
+use crate::traversal::TrieDfsTraversal;
+use crate::trie::Trie;
+
+/// parallel unordered iteration, one task per subtree below the root
+impl<K, V, A> Trie<K, V, A>
+where
+    K: PartialEq + Clone + Send + Sync,
+    V: Sync,
+    A: Aggregate<V>,
+    A::Summary: Sync,
+{
+    /// Like [`Trie::iter_values_unordered`], but searching the subtree below
+    /// each first key in parallel.
+    pub fn par_iter_values_unordered(
+        &self,
+        pattern: Option<Pattern<K>>,
+    ) -> impl ParallelIterator<Item = (impl Iterator<Item = &K>, &V)> {
+        // a traversal matches its start as if it were the root, so the
+        // pattern is stepped past the first key here instead
+        let mut starts = Vec::new();
+        if self.root().is_some() {
+            let initial =
+                pattern.as_ref().map(|p| p.initial()).unwrap_or_default();
+
+            for &i in self.child_indices(0) {
+                let states = match &pattern {
+                    None => States::new(),
+                    Some(pattern) => {
+                        match pattern.step(&initial, self.node(i).key()) {
+                            states if states.is_empty() => continue,
+                            states => states,
+                        }
+                    }
+                };
+                starts.push((i, states));
+            }
+        }
+
+        starts.into_par_iter().flat_map_iter(move |(i, states)| {
+            let subtree = TrieDfsTraversal::with_states(
+                self,
+                i,
+                pattern.clone(),
+                states,
+                Default::default(),
+                |_| false,
+            );
+            self.words(subtree)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use quickcheck_macros::quickcheck;
+
+    use super::*;
+
+    /// words as strings over a small alphabet, so that patterns match often
+    fn to_string(word: &[u8]) -> String {
+        word.iter().map(|&b| (b'a' + b % 3) as char).collect()
+    }
+
+    #[quickcheck]
+    fn test_par_matches_dfs(words: Vec<(Vec<u8>, u8)>, pattern: Vec<u8>) {
+        let mut trie = Trie::new();
+        for (word, value) in &words {
+            trie.push(to_string(word).chars(), *value);
+        }
+
+        let pattern = pattern
+            .iter()
+            .map(|&b| ['a', 'b', 'c', '?', '*'][b as usize % 5])
+            .collect::<String>();
+        let patterns = [None, Some(Pattern::try_from(&*pattern).unwrap())];
+
+        for pattern in patterns {
+            let sequential = trie
+                .iter_values_unordered(pattern.clone())
+                .map(|(path, v)| (path.collect::<String>(), *v))
+                .collect::<HashSet<_>>();
+            let parallel = trie
+                .par_iter_values_unordered(pattern)
+                .map(|(path, v)| (path.collect::<String>(), *v))
+                .collect::<HashSet<_>>();
+
+            assert_eq!(sequential, parallel);
+        }
+    }
+
+    #[test]
+    fn test_par_empty_word() {
+        let mut trie = Trie::new();
+        assert_eq!(trie.par_iter_values_unordered(None).count(), 0);
+
+        trie.push("".chars(), 1);
+        trie.push("a".chars(), 2);
+        let values = trie
+            .par_iter_values_unordered(None)
+            .map(|(_, v)| *v)
+            .collect::<HashSet<_>>();
+
+        assert_eq!(values, HashSet::from([1, 2]));
+    }
+}
@@ -0,0 +1,345 @@
+use crate::trie::Key;
+
+/// What a single slot of a pattern accepts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Query<K> {
+    /// exactly this key
+    Key(Key<K>),
+    /// any key, except for the `Key::Start`/`Key::End` sentinels
+    Any,
+    /// any one of these keys
+    OneOf(Vec<K>),
+    /// any key except these (or the sentinels)
+    NoneOf(Vec<K>),
+}
+
+impl<K: PartialEq> Query<K> {
+    fn matches(&self, key: &Key<K>) -> bool {
+        match self {
+            Query::Key(k) => k == key,
+            Query::Any => matches!(key, Key::Internal(_)),
+            Query::OneOf(ks) => {
+                matches!(key, Key::Internal(k) if ks.contains(k))
+            }
+            Query::NoneOf(ks) => {
+                matches!(key, Key::Internal(k) if !ks.contains(k))
+            }
+        }
+    }
+}
+
+/// How many keys a slot consumes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Repeat {
+    Once,
+    /// zero or one
+    Optional,
+    /// zero or more
+    Many,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Slot<K> {
+    query: Query<K>,
+    repeat: Repeat,
+}
+
+/// Positions in a pattern that a partial match could be at, i.e. a state of
+/// the NFA that the pattern describes. Position `slots.len()` means the whole
+/// pattern has been matched.
+///
+/// INVARIANT sorted without duplicates. Empty once matching has failed.
+pub(crate) type States = Vec<usize>;
+
+/// A pattern that words must match to be returned by a traversal.
+///
+/// ```
+/// use vocab_tree::Pattern;
+///
+/// // words of length three starting with "ca"
+/// let built = Pattern::new().key('c').key('a').any();
+/// let parsed = Pattern::try_from("ca?").unwrap();
+/// assert_eq!(built, parsed);
+///
+/// // words starting with "un" and ending with "ing"
+/// let parsed = Pattern::try_from("un*ing").unwrap();
+/// ```
+///
+/// Patterns are run as an NFA alongside the traversals, so subtrees of the
+/// trie that can't match are never visited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern<K> {
+    /// INVARIANT starts with `Key::Start` and ends with `Key::End`, which are
+    /// matched by the root of the trie and the end of a word respectively
+    slots: Vec<Slot<K>>,
+}
+
+impl<K> Default for Pattern<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Pattern<K> {
+    /// A pattern with no slots, matching only the empty word.
+    pub fn new() -> Self {
+        let once = |key| Slot {
+            query: Query::Key(key),
+            repeat: Repeat::Once,
+        };
+
+        Self {
+            slots: vec![once(Key::Start), once(Key::End)],
+        }
+    }
+
+    /// Adds a slot matching exactly `key`.
+    pub fn key(self, key: K) -> Self {
+        self.push(Query::Key(Key::Internal(key)), Repeat::Once)
+    }
+
+    /// Adds a slot matching any single key.
+    pub fn any(self) -> Self {
+        self.push(Query::Any, Repeat::Once)
+    }
+
+    /// Adds a slot matching any one of `keys`.
+    pub fn one_of(self, keys: impl IntoIterator<Item = K>) -> Self {
+        self.push(Query::OneOf(keys.into_iter().collect()), Repeat::Once)
+    }
+
+    /// Adds a slot matching any single key except `keys`.
+    pub fn none_of(self, keys: impl IntoIterator<Item = K>) -> Self {
+        self.push(Query::NoneOf(keys.into_iter().collect()), Repeat::Once)
+    }
+
+    /// Adds a slot matching any run of keys, including an empty one.
+    pub fn any_run(self) -> Self {
+        self.push(Query::Any, Repeat::Many)
+    }
+
+    /// Makes the most recently added slot optional. Has no effect on an
+    /// empty pattern.
+    pub fn optional(mut self) -> Self {
+        if !self.is_empty() {
+            let i = self.slots.len() - 2;
+            if self.slots[i].repeat == Repeat::Once {
+                self.slots[i].repeat = Repeat::Optional;
+            }
+        }
+        self
+    }
+
+    /// Number of slots in the pattern.
+    pub fn len(&self) -> usize {
+        self.slots.len() - 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Slots keep being added just before the trailing `Key::End`.
+    fn push(mut self, query: Query<K>, repeat: Repeat) -> Self {
+        self.slots
+            .insert(self.slots.len() - 1, Slot { query, repeat });
+        self
+    }
+}
+
+impl<K: PartialEq> Pattern<K> {
+    /// States after matching the `Key::Start` at the root of a traversal.
+    pub(crate) fn initial(&self) -> States {
+        self.step(&self.closure(vec![0]), &Key::Start)
+    }
+
+    /// States after matching one more key.
+    pub(crate) fn step(&self, states: &[usize], key: &Key<K>) -> States {
+        let next = states
+            .iter()
+            .filter_map(|&i| Some((i, self.slots.get(i)?)))
+            .filter(|(_, slot)| slot.query.matches(key))
+            .map(|(i, slot)| match slot.repeat {
+                Repeat::Many => i,
+                _ => i + 1,
+            })
+            .collect();
+
+        self.closure(next)
+    }
+
+    /// Adds the states reachable without consuming a key, i.e. by skipping
+    /// over optional slots.
+    fn closure(&self, mut states: States) -> States {
+        let mut j = 0;
+        while let Some(&i) = states.get(j) {
+            let skippable = self
+                .slots
+                .get(i)
+                .is_some_and(|slot| slot.repeat != Repeat::Once);
+
+            if skippable && !states.contains(&(i + 1)) {
+                states.push(i + 1);
+            }
+            j += 1;
+        }
+
+        states.sort_unstable();
+        states.dedup();
+        states
+    }
+}
+
+impl Pattern<char> {
+    /// Stands for any single character in a parsed pattern.
+    pub const WILDCARD: char = '?';
+
+    /// Stands for any run of characters in a parsed pattern.
+    pub const WILDCARD_RUN: char = '*';
+}
+
+/// Parses patterns like `"ca?"`, `"?A??E"`, `"UN*ING"` or `"[^ER]?[AEIOU]"`,
+/// where [`Pattern::WILDCARD`] matches any character,
+/// [`Pattern::WILDCARD_RUN`] matches any run of characters, `[...]` matches
+/// one of the bracketed characters, `[^...]` matches any character but them,
+/// and every other character matches itself.
+impl TryFrom<&str> for Pattern<char> {
+    type Error = &'static str;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut pattern = Self::new();
+        let mut chars = value.chars();
+
+        while let Some(c) = chars.next() {
+            pattern = match c {
+                Self::WILDCARD => pattern.any(),
+                Self::WILDCARD_RUN => pattern.any_run(),
+                '[' => {
+                    let rest = chars.as_str();
+                    let (class, rest) = rest
+                        .split_once(']')
+                        .ok_or("Unterminated '[' in pattern.")?;
+                    chars = rest.chars();
+
+                    match class.strip_prefix('^') {
+                        Some(class) => pattern.none_of(class.chars()),
+                        None => pattern.one_of(class.chars()),
+                    }
+                }
+                ']' => return Err("Unmatched ']' in pattern."),
+                c => pattern.key(c),
+            };
+        }
+
+        Ok(pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// whether the pattern matches the whole word
+    fn accepts(pattern: &Pattern<char>, word: &str) -> bool {
+        let states = word
+            .chars()
+            .map(Key::Internal)
+            .chain(std::iter::once(Key::End))
+            .fold(pattern.initial(), |states, key| pattern.step(&states, &key));
+
+        states.contains(&pattern.slots.len())
+    }
+
+    #[test]
+    fn test_parse() {
+        let pattern = Pattern::try_from("?A??E").unwrap();
+
+        assert_eq!(pattern, Pattern::new().any().key('A').any().any().key('E'));
+        assert_eq!(pattern.len(), 5);
+        assert_eq!(Pattern::try_from("").unwrap(), Pattern::new());
+        assert_eq!(
+            Pattern::try_from("a*").unwrap(),
+            Pattern::new().key('a').any_run()
+        );
+    }
+
+    #[test]
+    fn test_parse_classes() {
+        assert_eq!(
+            Pattern::try_from("[^er]?[aeiou]").unwrap(),
+            Pattern::new()
+                .none_of(['e', 'r'])
+                .any()
+                .one_of("aeiou".chars())
+        );
+        assert_eq!(Pattern::try_from("[]").unwrap(), Pattern::new().one_of([]));
+        assert!(Pattern::try_from("a[bc").is_err());
+        assert!(Pattern::try_from("ab]").is_err());
+    }
+
+    #[test]
+    fn test_classes() {
+        // wordle: not E or R in the first slot, a vowel in the last
+        let pattern = Pattern::try_from("[^er]??[aeiou]").unwrap();
+
+        assert!(accepts(&pattern, "oboe"));
+        assert!(accepts(&pattern, "data"));
+        assert!(!accepts(&pattern, "rota"));
+        assert!(!accepts(&pattern, "dark"));
+        assert!(!accepts(&pattern, "ata"));
+
+        // a class never matches the end of a word
+        let pattern = Pattern::try_from("a[^b]").unwrap();
+        assert!(accepts(&pattern, "ac"));
+        assert!(!accepts(&pattern, "a"));
+    }
+
+    #[test]
+    fn test_fixed_length() {
+        let pattern = Pattern::try_from("ca?").unwrap();
+
+        assert!(accepts(&pattern, "car"));
+        assert!(accepts(&pattern, "cat"));
+        assert!(!accepts(&pattern, "ca"));
+        assert!(!accepts(&pattern, "cart"));
+        assert!(!accepts(&pattern, "bat"));
+
+        assert!(accepts(&Pattern::new(), ""));
+        assert!(!accepts(&Pattern::new(), "a"));
+    }
+
+    #[test]
+    fn test_any_run() {
+        let pattern = Pattern::try_from("un*ing").unwrap();
+
+        assert!(accepts(&pattern, "unding"));
+        assert!(accepts(&pattern, "unsing"));
+        assert!(accepts(&pattern, "unbelieving"));
+        assert!(accepts(&pattern, "uning"));
+        assert!(!accepts(&pattern, "unin"));
+        assert!(!accepts(&pattern, "running"));
+
+        let pattern = Pattern::try_from("*").unwrap();
+        assert!(accepts(&pattern, ""));
+        assert!(accepts(&pattern, "anything"));
+    }
+
+    #[test]
+    fn test_optional() {
+        // "colo(u)r"
+        let pattern = Pattern::try_from("colo").unwrap().key('u').optional();
+        let pattern = pattern.key('r');
+
+        assert!(accepts(&pattern, "color"));
+        assert!(accepts(&pattern, "colour"));
+        assert!(!accepts(&pattern, "colouur"));
+
+        let pattern = Pattern::new().any().optional().any().optional();
+        assert!(accepts(&pattern, ""));
+        assert!(accepts(&pattern, "a"));
+        assert!(accepts(&pattern, "ab"));
+        assert!(!accepts(&pattern, "abc"));
+
+        assert_eq!(Pattern::<char>::new().optional(), Pattern::new());
+    }
+}
@@ -0,0 +1,142 @@
+//! Compact binary snapshots of tries, so that a vocabulary can be built once
+//! and loaded quickly afterwards.
+//!
+//! A snapshot is a short header (magic bytes followed by a little-endian
+//! format version) and then the bincode encoding of the trie.
+
+use std::error::Error;
+use std::io::{Read, Write};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{Aggregate, Trie};
+
+const MAGIC: &[u8; 4] = b"VTRI";
+
+/// Bump whenever the layout of the trie changes.
+const VERSION: u32 = 6;
+
+impl<K, V, A> Trie<K, V, A>
+where
+    K: Serialize,
+    V: Serialize,
+    A: Aggregate<V>,
+    A::Summary: Serialize,
+{
+    pub fn write_snapshot<W: Write>(
+        &self,
+        mut writer: W,
+    ) -> Result<(), Box<dyn Error>> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        bincode::serialize_into(writer, self)?;
+        Ok(())
+    }
+}
+
+impl<K, V, A> Trie<K, V, A>
+where
+    K: PartialEq + DeserializeOwned,
+    V: DeserializeOwned,
+    A: Aggregate<V>,
+    A::Summary: DeserializeOwned,
+{
+    pub fn read_snapshot<R: Read>(
+        mut reader: R,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err("Not a trie snapshot.".into());
+        }
+
+        let mut version = [0; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != VERSION {
+            return Err(
+                format!("Unsupported snapshot version {version}.").into()
+            );
+        }
+
+        let trie: Self = bincode::deserialize_from(reader)?;
+        trie.check_invariants()?;
+
+        Ok(trie)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut trie = Trie::new();
+        trie.push("car".chars(), 3);
+        trie.push("cat".chars(), 1);
+        trie.push("dog".chars(), 2);
+
+        let mut bytes = Vec::new();
+        trie.write_snapshot(&mut bytes).unwrap();
+
+        let loaded: Trie<char, i32> =
+            Trie::read_snapshot(bytes.as_slice()).unwrap();
+
+        assert_eq!(loaded.get("car".chars()), Some(&3));
+        assert_eq!(loaded.get("cat".chars()), Some(&1));
+        assert_eq!(loaded.get("dog".chars()), Some(&2));
+        assert_eq!(
+            loaded
+                .iter_values_ordered(None)
+                .map(|(_, v)| *v)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_bad_header() {
+        let mut trie = Trie::new();
+        trie.push("car".chars(), 3);
+
+        let mut bytes = Vec::new();
+        trie.write_snapshot(&mut bytes).unwrap();
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[4] += 1;
+        assert!(
+            Trie::<char, i32>::read_snapshot(wrong_version.as_slice()).is_err()
+        );
+
+        assert!(Trie::<char, i32>::read_snapshot(&b"hello world"[..]).is_err());
+    }
+
+    #[test]
+    fn test_corrupt_indices() {
+        let mut trie = Trie::new();
+        trie.push("car".chars(), 3);
+        trie.push("cat".chars(), 1);
+
+        let mut bytes = Vec::new();
+        trie.write_snapshot(&mut bytes).unwrap();
+
+        // corrupt each byte in turn: whatever still decodes must be safe to
+        // use, and corrupted indices must be caught
+        let mut rejected = 0;
+        for offset in 8..bytes.len() {
+            let mut corrupt = bytes.clone();
+            corrupt[offset] ^= 0x40;
+            match Trie::<char, i32>::read_snapshot(corrupt.as_slice()) {
+                Ok(loaded) => {
+                    // anything accepted must be safe to use
+                    loaded.iter_values_ordered(None).count();
+                    loaded.get("car".chars());
+                }
+                Err(_) => rejected += 1,
+            }
+        }
+        assert!(rejected > 0);
+    }
+}
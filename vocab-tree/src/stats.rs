@@ -0,0 +1,178 @@
+use std::fmt::{Debug, Write};
+
+use crate::aggregate::Aggregate;
+use crate::trie::Key;
+use crate::Trie;
+
+/// The shape of a trie, as reported by [`Trie::stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    /// nodes in use, including the `Start` and `End` sentinels
+    pub node_count: usize,
+    pub word_count: usize,
+    /// `depths[d]` is the number of distinct prefixes of length `d`
+    pub depths: Vec<usize>,
+    /// mean number of children of the nodes that have any
+    pub branching_factor: f64,
+    /// memory allocated for the nodes, not counting any heap memory owned by
+    /// the keys and values themselves
+    pub heap_bytes: usize,
+}
+
+impl<K, V, A: Aggregate<V>> Trie<K, V, A> {
+    pub fn stats(&self) -> Stats {
+        let mut word_count = 0;
+        let mut depths = Vec::new();
+        let (mut parents, mut edges) = (0, 0);
+
+        let mut stack = Vec::new();
+        if self.root().is_some() {
+            stack.push((0, 0));
+        }
+        while let Some((index, depth)) = stack.pop() {
+            match self.node(index).key() {
+                Key::End => word_count += 1,
+                _ => {
+                    if depths.len() <= depth {
+                        depths.push(0);
+                    }
+                    depths[depth] += 1;
+                }
+            }
+
+            let children = self.child_indices(index);
+            if !children.is_empty() {
+                parents += 1;
+                edges += children.len();
+            }
+            for &child in children {
+                let depth = match self.node(child).key() {
+                    Key::Internal(_) => depth + 1,
+                    _ => depth,
+                };
+                stack.push((child, depth));
+            }
+        }
+
+        Stats {
+            node_count: self.node_count(),
+            word_count,
+            depths,
+            branching_factor: match parents {
+                0 => 0.0,
+                _ => edges as f64 / parents as f64,
+            },
+            heap_bytes: self.heap_bytes(),
+        }
+    }
+}
+
+impl<K, V, A> Trie<K, V, A>
+where
+    K: PartialEq + Debug,
+    A: Aggregate<V>,
+    A::Summary: Debug,
+{
+    /// The subtree of every word starting with `prefix` as a Graphviz DOT
+    /// graph, with each node labeled by its key and summary.
+    pub fn to_dot(
+        &self,
+        prefix: impl IntoIterator<Item = K>,
+    ) -> Option<String> {
+        let start =
+            self.get_node_index(prefix.into_iter().map(Key::Internal))?;
+
+        let mut dot = String::from("digraph trie {\n");
+        let mut stack = vec![start];
+        while let Some(index) = stack.pop() {
+            let node = self.node(index);
+            let key = match node.key() {
+                Key::Start => "Start".to_string(),
+                Key::Internal(k) => format!("{k:?}"),
+                Key::End => "End".to_string(),
+            };
+            let summary = format!("{:?}", node.summary());
+            writeln!(
+                dot,
+                "    n{index} [label=\"{}\\n{}\"];",
+                escape(&key),
+                escape(&summary)
+            )
+            .unwrap();
+
+            for &child in self.child_indices(index) {
+                writeln!(dot, "    n{index} -> n{child};").unwrap();
+                stack.push(child);
+            }
+        }
+        dot.push_str("}\n");
+
+        Some(dot)
+    }
+}
+
+/// escapes a label for a double-quoted DOT string
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Count;
+
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let mut trie = Trie::new();
+        assert_eq!(trie.stats().node_count, 0);
+        assert_eq!(trie.stats().depths, Vec::<usize>::new());
+
+        for (word, cost) in [("car", 3), ("cat", 1), ("ca", 2), ("dog", 5)] {
+            trie.push(word.chars(), cost);
+        }
+        let stats = trie.stats();
+
+        // Start, c, a, r, t, d, o, g and four End nodes
+        assert_eq!(stats.node_count, 12);
+        assert_eq!(stats.word_count, 4);
+        assert_eq!(stats.depths, vec![1, 2, 2, 3]);
+        // the root has two children, "ca" three and the other non-leaves one
+        assert_eq!(stats.branching_factor, 11.0 / 8.0);
+        assert!(stats.heap_bytes > 0);
+
+        // removed words leave free slots, which aren't counted as nodes
+        trie.remove("dog".chars());
+        let stats = trie.stats();
+        assert_eq!(stats.node_count, 8);
+        assert_eq!(stats.word_count, 3);
+        assert_eq!(stats.depths, vec![1, 1, 1, 2]);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut trie = Trie::<_, _, Count>::default();
+        for (word, value) in [("ab", ()), ("a\"", ())] {
+            trie.push(word.chars(), value);
+        }
+
+        assert_eq!(trie.to_dot("b".chars()), None);
+        assert_eq!(
+            trie.to_dot("a".chars()).unwrap(),
+            [
+                "digraph trie {",
+                "    n1 [label=\"'a'\\n2\"];",
+                "    n1 -> n2;",
+                "    n1 -> n4;",
+                "    n4 [label=\"'\\\"'\\n1\"];",
+                "    n4 -> n5;",
+                "    n5 [label=\"End\\n1\"];",
+                "    n2 [label=\"'b'\\n1\"];",
+                "    n2 -> n3;",
+                "    n3 [label=\"End\\n1\"];",
+                "}\n",
+            ]
+            .join("\n")
+        );
+    }
+}
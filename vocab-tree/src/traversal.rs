@@ -1,107 +1,289 @@
-// TODO: implement DFS as a special case of dijkstras
-
-use std::fmt::Debug;
-
+use crate::aggregate::Aggregate;
+use crate::frontier::{Candidate, Frontier, Queue, Stack};
+use crate::pattern::{Pattern, States};
 use crate::trie::{Key, Node, Trie};
 
-#[derive(Debug)]
-pub(crate) struct TrieDfsTraversal<'a, K, V> {
-    trie: &'a Trie<K, V>,
-    stack: Vec<(usize, usize)>, // index, depth
+/// Visits the nodes of a trie that can match `pattern`, in the order given by
+/// the frontier. Nodes whose summary `prune` returns `true` for are skipped
+/// along with their subtrees.
+pub(crate) struct Traversal<'a, K, V, A: Aggregate<V>, F, P> {
+    trie: &'a Trie<K, V, A>,
+    frontier: F,
     pattern: Option<Pattern<K>>,
+    prune: P,
 }
 
-pub(crate) type Pattern<K> = Vec<Query<K>>;
-pub(crate) type Query<K> = Option<Key<K>>;
+pub(crate) type NoPrune<S> = fn(&S) -> bool;
 
-impl<'a, K, V> TrieDfsTraversal<'a, K, V> {
-    pub(crate) fn from_root(
-        trie: &'a Trie<K, V>,
+pub(crate) type TrieDfsTraversal<'a, K, V, A> = Traversal<
+    'a,
+    K,
+    V,
+    A,
+    Stack<'a, <A as Aggregate<V>>::Summary>,
+    NoPrune<<A as Aggregate<V>>::Summary>,
+>;
+
+impl<'a, K, V, A, F, P> Traversal<'a, K, V, A, F, P>
+where
+    K: PartialEq,
+    A: Aggregate<V>,
+    F: Frontier<'a, A::Summary>,
+    P: FnMut(&A::Summary) -> bool,
+{
+    /// `pattern` is matched as if the node at `start_index` were the root
+    pub(crate) fn new(
+        trie: &'a Trie<K, V, A>,
+        start_index: usize,
         pattern: Option<Pattern<K>>,
+        frontier: F,
+        prune: P,
     ) -> Self {
-        Self::from_index(trie, 0, pattern)
+        let states = pattern.as_ref().map(|p| p.initial()).unwrap_or_default();
+
+        Self::with_states(trie, start_index, pattern, states, frontier, prune)
     }
 
-    pub(crate) fn from_index(
-        trie: &'a Trie<K, V>,
+    /// `states` are the pattern states reached at the node at `start_index`
+    pub(crate) fn with_states(
+        trie: &'a Trie<K, V, A>,
         start_index: usize,
         pattern: Option<Pattern<K>>,
+        states: States,
+        mut frontier: F,
+        mut prune: P,
     ) -> Self {
+        if trie.root().is_some() {
+            let start = trie.node(start_index);
+
+            if !prune(start.summary()) {
+                frontier.push(Candidate::new(
+                    start.summary(),
+                    0,
+                    start_index,
+                    states,
+                ));
+            }
+        }
+
         Self {
             trie,
-            stack: if trie.root().is_some() {
-                vec![(start_index, 0)]
-            } else {
-                vec![]
-            },
+            frontier,
             pattern,
+            prune,
         }
     }
 }
 
-impl<'a, K, V> Iterator for TrieDfsTraversal<'a, K, V>
+impl<'a, K, V, A, F> Traversal<'a, K, V, A, F, NoPrune<A::Summary>>
 where
     K: PartialEq,
+    A: Aggregate<V>,
+    F: Frontier<'a, A::Summary> + Default,
 {
-    type Item = &'a Node<K, V>;
+    pub(crate) fn from_root(
+        trie: &'a Trie<K, V, A>,
+        pattern: Option<Pattern<K>>,
+    ) -> Self {
+        Self::from_index(trie, 0, pattern)
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some((index, depth)) = self.stack.pop() {
-            let remainig_pattern =
-                self.pattern.as_ref().map(|p| &p[depth + 1..]);
-
-            // push the approiate children based on the remaining pattern
-            match remainig_pattern {
-                // no pattern OR next part of pattern is `None`:
-                // push all children
-                None | Some([None, ..]) => self.stack.extend(
-                    self.trie
-                        .child_indices(index)
-                        .iter()
-                        .map(|i| (*i, depth + 1)),
-                ),
-                // next part of pattern is `Some`:
-                // push the matching child (if it exists)
-                Some([Some(k), ..]) => {
-                    if let Some(child_index) =
-                        self.trie.get_child_index(index, k)
-                    {
-                        self.stack.push((child_index, depth + 1));
+    pub(crate) fn from_index(
+        trie: &'a Trie<K, V, A>,
+        start_index: usize,
+        pattern: Option<Pattern<K>>,
+    ) -> Self {
+        Self::new(trie, start_index, pattern, F::default(), |_| false)
+    }
+}
+
+impl<'a, K, V, A, F, P> Traversal<'a, K, V, A, F, P>
+where
+    K: PartialEq,
+    A: Aggregate<V>,
+    F: Frontier<'a, A::Summary>,
+    P: FnMut(&A::Summary) -> bool,
+{
+    /// whether every node has been visited
+    pub(crate) fn is_done(&self) -> bool {
+        self.frontier.is_empty()
+    }
+
+    /// the next node along with its depth below the start of the traversal
+    pub(crate) fn next_with_depth(
+        &mut self,
+    ) -> Option<(&'a Node<K, V, A>, usize)> {
+        let current = self.frontier.pop()?;
+
+        for &i in self.trie.child_indices(current.index) {
+            let child = self.trie.node(i);
+
+            // push the children that can continue the match
+            let states = match &self.pattern {
+                None => States::new(),
+                Some(pattern) => {
+                    match pattern.step(&current.states, child.key()) {
+                        states if states.is_empty() => continue,
+                        states => states,
                     }
                 }
-                // end of pattern. don't descend further
-                Some([]) => {}
+            };
+
+            if (self.prune)(child.summary()) {
+                continue;
             }
 
-            // return the current index
-            Some(self.trie.node(index))
-        } else {
-            // exhausted tree
-            None
+            self.frontier.push(Candidate::new(
+                child.summary(),
+                current.depth() + 1,
+                i,
+                states,
+            ));
         }
+
+        Some((self.trie.node(current.index), current.depth()))
+    }
+}
+
+impl<'a, K, V, A, F, P> Iterator for Traversal<'a, K, V, A, F, P>
+where
+    K: PartialEq,
+    A: Aggregate<V>,
+    F: Frontier<'a, A::Summary>,
+    P: FnMut(&A::Summary) -> bool,
+{
+    type Item = &'a Node<K, V, A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_depth().map(|(node, _)| node)
+    }
+}
+
+impl<K, V, A: Aggregate<V>> Trie<K, V, A> {
+    /// the words among `nodes`, i.e. the `Key::End` nodes
+    pub(crate) fn words<'a>(
+        &'a self,
+        nodes: impl Iterator<Item = &'a Node<K, V, A>>,
+    ) -> impl Iterator<Item = (impl Iterator<Item = &'a K>, &'a V)> {
+        nodes.filter_map(|node| Some((self.path_to_root(node), node.value()?)))
     }
 }
 
 /// unordered iteration over nodes
-impl<K, V> Trie<K, V>
+impl<K, V, A> Trie<K, V, A>
 where
     K: PartialEq,
+    A: Aggregate<V>,
 {
     pub fn iter_values_unordered(
         &self,
         pattern: Option<Pattern<K>>,
     ) -> impl Iterator<Item = (impl Iterator<Item = &K>, &V)> {
-        TrieDfsTraversal::from_root(self, pattern).filter_map(|node| match node
-            .key()
-        {
-            Key::End => Some((self.path_to_root(node), node.value())),
-            _ => None,
+        self.words(TrieDfsTraversal::from_root(self, pattern))
+    }
+
+    /// Like [`Trie::iter_values_unordered`], but yielding each word front to
+    /// back.
+    pub fn iter_keys_unordered(
+        &self,
+        pattern: Option<Pattern<K>>,
+    ) -> impl Iterator<Item = (Vec<K>, &V)>
+    where
+        K: Clone,
+    {
+        self.iter_words_unordered(pattern, |path| {
+            path.iter().copied().cloned().collect()
+        })
+    }
+
+    /// Unordered words, built from the path to the current node which a DFS
+    /// keeps track of for free.
+    fn iter_words_unordered<'a, W>(
+        &'a self,
+        pattern: Option<Pattern<K>>,
+        collect: impl Fn(&[&'a K]) -> W,
+    ) -> impl Iterator<Item = (W, &'a V)> {
+        let mut traversal = TrieDfsTraversal::from_root(self, pattern);
+        let mut path = Vec::new();
+
+        std::iter::from_fn(move || loop {
+            let (node, depth) = traversal.next_with_depth()?;
+
+            // nodes come out in pre-order, so the path holds exactly the
+            // ancestors of `node` once it's cut down to size
+            path.truncate(depth.saturating_sub(1));
+
+            match (node.key(), node.value()) {
+                (Key::Internal(k), _) => path.push(k),
+                (Key::End, Some(value)) => {
+                    return Some((collect(&path), value))
+                }
+                _ => {}
+            }
+        })
+    }
+
+    /// Like [`Trie::iter_values_unordered`], but shorter words come first.
+    pub fn iter_values_shortest_first(
+        &self,
+        pattern: Option<Pattern<K>>,
+    ) -> impl Iterator<Item = (impl Iterator<Item = &K>, &V)> {
+        self.words(Traversal::<_, _, _, Queue<_>, _>::from_root(self, pattern))
+    }
+
+    /// Words matching `pattern`, in the order that `frontier` visits them.
+    /// Subtrees whose summary `prune` returns `true` for are skipped.
+    ///
+    /// ```
+    /// use vocab_tree::{Beam, Trie};
+    ///
+    /// let mut trie = Trie::new();
+    /// trie.push("cart".chars(), 3);
+    /// trie.push("cat".chars(), 1);
+    /// trie.push("dog".chars(), 2);
+    ///
+    /// // best-first, but only keeping the single best node around
+    /// let words = trie.traverse(None, Beam::new(1), |_| false).count();
+    /// assert_eq!(words, 1);
+    ///
+    /// // skip everything worse than 2
+    /// let words = trie.traverse(None, Beam::new(10), |&v| v > 2).count();
+    /// assert_eq!(words, 2);
+    /// ```
+    pub fn traverse<'a, F, P>(
+        &'a self,
+        pattern: Option<Pattern<K>>,
+        frontier: F,
+        prune: P,
+    ) -> impl Iterator<Item = (impl Iterator<Item = &'a K>, &'a V)>
+    where
+        F: Frontier<'a, A::Summary>,
+        P: FnMut(&A::Summary) -> bool,
+    {
+        self.words(Traversal::new(self, 0, pattern, frontier, prune))
+    }
+}
+
+impl<V, A: Aggregate<V>> Trie<char, V, A> {
+    /// Like [`Trie::iter_keys_unordered`], but yielding `String`s.
+    pub fn iter_strings_unordered(
+        &self,
+        pattern: Option<Pattern<char>>,
+    ) -> impl Iterator<Item = (String, &V)> {
+        self.iter_words_unordered(pattern, |path| {
+            path.iter().copied().collect()
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
+    use quickcheck_macros::quickcheck;
+
+    use crate::frontier::Stack;
+
     use super::*;
 
     #[test]
@@ -117,15 +299,7 @@ mod tests {
     #[test]
     fn test_dfs_with_pattern() {
         let mut trie = Trie::new();
-
-        // 'ca.'
-        let pattern = vec![
-            Some(Key::Start),
-            Some(Key::Internal('c')),
-            Some(Key::Internal('a')),
-            None,
-            Some(Key::End),
-        ];
+        let pattern = Pattern::try_from("ca?").unwrap();
 
         trie.push("car".chars(), ());
         trie.push("cat".chars(), ());
@@ -145,6 +319,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_wildcard_needs_a_key() {
+        let mut trie = Trie::new();
+        trie.push("ca".chars(), ());
+        trie.push("cat".chars(), ());
+
+        let pattern = Pattern::try_from("ca?").unwrap();
+        let matches = trie
+            .iter_values_unordered(Some(pattern))
+            .map(|(prefix, _)| prefix.count())
+            .collect::<Vec<_>>();
+
+        assert_eq!(matches, vec![3]);
+    }
+
+    #[test]
+    fn test_classes() {
+        let mut trie = Trie::new();
+        for word in ["cat", "cot", "cut", "cab", "bat"] {
+            trie.push(word.chars(), ());
+        }
+
+        let pattern = Pattern::try_from("[bc][^u]t").unwrap();
+        let mut matches = trie
+            .iter_strings_unordered(Some(pattern))
+            .map(|(word, _)| word)
+            .collect::<Vec<_>>();
+        matches.sort();
+
+        assert_eq!(matches, vec!["bat", "cat", "cot"]);
+    }
+
+    #[test]
+    fn test_any_run() {
+        let mut trie = Trie::new();
+        for word in ["undoing", "unsung", "uncaring", "doing", "unkind", "un"] {
+            trie.push(word.chars(), ());
+        }
+
+        let pattern = Pattern::try_from("un*ing").unwrap();
+        let mut matches = trie
+            .iter_strings_unordered(Some(pattern))
+            .map(|(word, _)| word)
+            .collect::<Vec<_>>();
+        matches.sort();
+
+        assert_eq!(matches, vec!["uncaring", "undoing"]);
+    }
+
+    #[test]
+    fn test_iter_keys_unordered() {
+        let mut trie = Trie::new();
+        for (word, value) in [("car", 1), ("carpet", 2), ("cat", 3), ("", 4)] {
+            trie.push(word.chars(), value);
+        }
+
+        let mut words = trie.iter_keys_unordered(None).collect::<Vec<_>>();
+        words.sort();
+
+        assert_eq!(
+            words,
+            vec![
+                (vec![], &4),
+                (vec!['c', 'a', 'r'], &1),
+                (vec!['c', 'a', 'r', 'p', 'e', 't'], &2),
+                (vec!['c', 'a', 't'], &3),
+            ]
+        );
+
+        let mut strings = trie
+            .iter_strings_unordered(Some(Pattern::try_from("ca?").unwrap()))
+            .collect::<Vec<_>>();
+        strings.sort();
+
+        assert_eq!(strings, vec![("car".into(), &1), ("cat".into(), &3)]);
+    }
+
+    #[test]
+    fn test_shortest_first() {
+        let mut trie = Trie::new();
+        for word in ["carpet", "car", "ca", "cart", "a"] {
+            trie.push(word.chars(), ());
+        }
+
+        let lengths = trie
+            .iter_values_shortest_first(None)
+            .map(|(prefix, _)| prefix.count())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lengths, vec![1, 2, 3, 4, 6]);
+    }
+
+    #[test]
+    fn test_prune() {
+        let mut trie = Trie::new();
+        for (word, value) in [("car", 1), ("cat", 5), ("dog", 3), ("do", 9)] {
+            trie.push(word.chars(), value);
+        }
+
+        let mut values = trie
+            .traverse(None, Stack::default(), |&v| v > 3)
+            .map(|(_, v)| *v)
+            .collect::<Vec<_>>();
+        values.sort();
+
+        assert_eq!(values, vec![1, 3]);
+    }
+
     #[test]
     fn test_iter_unordered() {
         let mut trie = Trie::new();
@@ -169,4 +451,19 @@ mod tests {
 
         assert_eq!(words, should_be_words);
     }
+
+    #[quickcheck]
+    fn test_iter_strings_roundtrip(strings: HashSet<String>) {
+        let mut trie = Trie::new();
+        for s in &strings {
+            trie.push(s.chars(), ());
+        }
+
+        let returned = trie
+            .iter_strings_unordered(None)
+            .map(|(word, _)| word)
+            .collect::<HashSet<_>>();
+
+        assert_eq!(returned, strings);
+    }
 }
@@ -1,128 +1,192 @@
-use std::cmp::Reverse;
-use std::collections::BinaryHeap;
-use std::fmt::Debug;
-
-use crate::traversal::Pattern;
-use crate::trie::{Key, Node, Trie};
-
-#[derive(Debug)]
-pub(crate) struct DijkstraTraversal<'a, K, V> {
-    trie: &'a Trie<K, V>,
-    heap: BinaryHeap<HeapItem<&'a V>>, // cost, index, depth
-    pattern: Option<Pattern<K>>,
-}
+use crate::aggregate::Aggregate;
+use crate::frontier::Heap;
+use crate::pattern::Pattern;
+use crate::traversal::{NoPrune, Traversal};
+use crate::trie::{Key, Trie};
 
-#[derive(Debug, PartialEq, PartialOrd, Ord, Eq)]
-struct HeapItem<V> {
-    value: Reverse<V>,
-    index: usize,
-    depth: usize,
-}
+pub(crate) type DijkstraTraversal<'a, K, V, A> = Traversal<
+    'a,
+    K,
+    V,
+    A,
+    Heap<'a, <A as Aggregate<V>>::Summary>,
+    NoPrune<<A as Aggregate<V>>::Summary>,
+>;
 
-impl<V> HeapItem<V> {
-    fn new(value: V, index: usize, depth: usize) -> Self {
-        Self {
-            value: Reverse(value),
-            index,
-            depth,
-        }
+/// ordered iteration over nodes, by summary
+impl<K, V, A> Trie<K, V, A>
+where
+    K: PartialEq,
+    A: Aggregate<V>,
+    A::Summary: Ord,
+{
+    pub fn iter_values_ordered(
+        &self,
+        pattern: Option<Pattern<K>>,
+    ) -> impl Iterator<Item = (impl Iterator<Item = &K>, &V)> {
+        self.words(DijkstraTraversal::from_root(self, pattern))
+    }
+
+    /// Like [`Trie::iter_values_ordered`], but yielding each word front to
+    /// back.
+    pub fn iter_keys_ordered(
+        &self,
+        pattern: Option<Pattern<K>>,
+    ) -> impl Iterator<Item = (Vec<K>, &V)>
+    where
+        K: Clone,
+    {
+        self.iter_values_ordered(pattern)
+            .map(|(path, v)| (front_to_back(path), v))
     }
-}
 
-impl<'a, K, V: Ord> DijkstraTraversal<'a, K, V> {
-    pub(crate) fn from_root(
-        trie: &'a Trie<K, V>,
+    /// Like [`Trie::iter_keys_ordered`], but only the words whose summary is
+    /// at most `ceiling`. Subtrees with a summary above it are never visited,
+    /// so the traversal ends as soon as the remaining words get too costly.
+    pub fn iter_keys_within(
+        &self,
         pattern: Option<Pattern<K>>,
-    ) -> Self {
-        Self::from_index(trie, 0, pattern)
+        ceiling: A::Summary,
+    ) -> impl Iterator<Item = (Vec<K>, &V)>
+    where
+        K: Clone,
+    {
+        let too_costly = move |summary: &A::Summary| *summary > ceiling;
+        let traversal =
+            Traversal::new(self, 0, pattern, Heap::default(), too_costly);
+
+        self.words(traversal)
+            .map(|(path, v)| (front_to_back(path), v))
     }
 
-    pub(crate) fn from_index(
-        trie: &'a Trie<K, V>,
-        start_index: usize,
+    /// Like [`Trie::iter_keys_ordered`], but giving up after visiting
+    /// `max_nodes` nodes. [`Budgeted::budget_exhausted`] tells whether words
+    /// may have been missed.
+    pub fn iter_keys_budgeted(
+        &self,
         pattern: Option<Pattern<K>>,
-    ) -> Self {
-        let mut heap = BinaryHeap::new();
-        if let Some(root) = trie.root() {
-            heap.push(HeapItem::new(root.value(), start_index, 0));
+        max_nodes: usize,
+    ) -> Budgeted<'_, K, V, A> {
+        Budgeted {
+            trie: self,
+            traversal: DijkstraTraversal::from_root(self, pattern),
+            remaining: max_nodes,
+            exhausted: false,
         }
+    }
 
-        Self {
-            trie,
-            heap,
-            pattern,
-        }
+    /// The (up to) `k` best words starting with `prefix`, in summary order
+    /// and front to back. Only as much of the trie as is needed to find them
+    /// is visited.
+    pub fn top_k(
+        &self,
+        prefix: impl IntoIterator<Item = K>,
+        k: usize,
+    ) -> impl Iterator<Item = (Vec<K>, &V)>
+    where
+        K: Clone,
+    {
+        let start_index =
+            self.get_node_index(prefix.into_iter().map(Key::Internal));
+
+        let nodes = start_index
+            .into_iter()
+            .flat_map(|i| DijkstraTraversal::from_index(self, i, None));
+
+        self.words(nodes)
+            .take(k)
+            .map(|(path, v)| (front_to_back(path), v))
     }
 }
 
-impl<'a, K, V> Iterator for DijkstraTraversal<'a, K, V>
+/// Ordered iterator over words that stops after visiting a fixed number of
+/// nodes. Returned by [`Trie::iter_keys_budgeted`].
+pub struct Budgeted<'a, K, V, A: Aggregate<V>> {
+    trie: &'a Trie<K, V, A>,
+    traversal: DijkstraTraversal<'a, K, V, A>,
+    /// nodes that may still be visited
+    remaining: usize,
+    exhausted: bool,
+}
+
+impl<K, V, A: Aggregate<V>> Budgeted<'_, K, V, A> {
+    /// Whether the budget ran out with nodes left unvisited. Only known once
+    /// the iterator has returned `None`.
+    pub fn budget_exhausted(&self) -> bool {
+        self.exhausted
+    }
+}
+
+impl<'a, K, V, A> Iterator for Budgeted<'a, K, V, A>
 where
-    K: PartialEq,
-    V: Debug + Ord,
+    K: Clone + PartialEq,
+    A: Aggregate<V>,
+    A::Summary: Ord,
 {
-    type Item = &'a Node<K, V>;
+    type Item = (Vec<K>, &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(HeapItem { index, depth, .. }) = self.heap.pop() {
-            let remainig_pattern =
-                self.pattern.as_ref().map(|p| &p[depth + 1..]);
-
-            // push the approiate children based on the remaining pattern
-            match remainig_pattern {
-                // no pattern OR next part of pattern is `None`:
-                // push all children
-                None | Some([None, ..]) => self.heap.extend(
-                    self.trie.child_indices(index).iter().map(|&child_index| {
-                        HeapItem::new(
-                            self.trie.node(child_index).value(),
-                            child_index,
-                            depth + 1,
-                        )
-                    }),
-                ),
-                // next part of pattern is `Some`:
-                // push the matching child (if it exists)
-                Some([Some(k), ..]) => {
-                    if let Some(child_index) =
-                        self.trie.get_child_index(index, k)
-                    {
-                        self.heap.push(HeapItem::new(
-                            self.trie.node(child_index).value(),
-                            child_index,
-                            depth + 1,
-                        ));
-                    }
-                }
-                // end of pattern. don't descend further
-                Some([]) => {}
+        loop {
+            if self.remaining == 0 {
+                // a node left over means the traversal was cut short
+                self.exhausted = !self.traversal.is_done();
+                return None;
             }
+            self.remaining -= 1;
 
-            // return the current index
-            Some(self.trie.node(index))
-        } else {
-            // exhausted tree
-            None
+            let node = self.traversal.next()?;
+            if let Some(value) = node.value() {
+                let word = front_to_back(self.trie.path_to_root(node));
+                return Some((word, value));
+            }
         }
     }
 }
 
-/// ordered iteration over nodes
-impl<K, V> Trie<K, V>
+impl<V, A> Trie<char, V, A>
 where
-    K: PartialEq,
-    V: Debug + Ord,
+    A: Aggregate<V>,
+    A::Summary: Ord,
 {
-    pub fn iter_values_ordered(
+    /// Like [`Trie::iter_keys_ordered`], but yielding `String`s.
+    pub fn iter_strings_ordered(
         &self,
-        pattern: Option<Pattern<K>>,
-    ) -> impl Iterator<Item = (impl Iterator<Item = &K>, &V)> {
-        DijkstraTraversal::from_root(self, pattern).filter_map(
-            |node| match node.key() {
-                Key::End => Some((self.path_to_root(node), node.value())),
-                _ => None,
-            },
-        )
+        pattern: Option<Pattern<char>>,
+    ) -> impl Iterator<Item = (String, &V)> {
+        self.iter_keys_ordered(pattern)
+            .map(|(word, v)| (word.into_iter().collect(), v))
     }
+
+    /// Like [`Trie::iter_keys_within`], but yielding `String`s.
+    pub fn iter_strings_within(
+        &self,
+        pattern: Option<Pattern<char>>,
+        ceiling: A::Summary,
+    ) -> impl Iterator<Item = (String, &V)> {
+        self.iter_keys_within(pattern, ceiling)
+            .map(|(word, v)| (word.into_iter().collect(), v))
+    }
+
+    /// Like [`Trie::top_k`], but yielding `String`s.
+    pub fn top_k_strings(
+        &self,
+        prefix: &str,
+        k: usize,
+    ) -> impl Iterator<Item = (String, &V)> {
+        // collected so that the result doesn't borrow `prefix`
+        let prefix = prefix.chars().collect::<Vec<_>>();
+        self.top_k(prefix, k)
+            .map(|(word, v)| (word.into_iter().collect(), v))
+    }
+}
+
+/// a word from the path up to the root, which yields its keys back to front
+fn front_to_back<'a, K: Clone + 'a>(
+    path: impl Iterator<Item = &'a K>,
+) -> Vec<K> {
+    let mut word = path.cloned().collect::<Vec<_>>();
+    word.reverse();
+    word
 }
 
 #[cfg(test)]
@@ -159,6 +223,156 @@ mod tests {
         assert_eq!(sorted_by_last_letter, returned_by_iterator);
     }
 
+    #[test]
+    fn test_iter_ordered_with_pattern() {
+        let mut trie = Trie::new();
+
+        let words = [
+            ("unending", 3),
+            ("undoing", 1),
+            ("unkind", 0),
+            ("uncaring", 2),
+            ("caring", 0),
+        ];
+        for (word, popularity) in words {
+            trie.push(word.chars(), popularity);
+        }
+
+        let pattern = Pattern::try_from("un*ing").unwrap();
+        let returned_by_iterator = trie
+            .iter_strings_ordered(Some(pattern))
+            .map(|(word, _)| word)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            returned_by_iterator,
+            vec!["undoing", "uncaring", "unending"]
+        );
+    }
+
+    #[test]
+    fn test_top_k() {
+        let mut trie = Trie::new();
+
+        let words = [("cat", 3), ("car", 1), ("cart", 0), ("dog", 2)];
+        for (word, popularity) in words {
+            trie.push(word.chars(), popularity);
+        }
+
+        let top_k = |prefix: &str, k| {
+            trie.top_k_strings(prefix, k)
+                .map(|(word, v)| (word, *v))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(top_k("ca", 2), vec![("cart".into(), 0), ("car".into(), 1)]);
+        assert_eq!(top_k("cat", 5), vec![("cat".into(), 3)]);
+        assert_eq!(top_k("", 1), vec![("cart".into(), 0)]);
+        assert_eq!(top_k("ca", 0), vec![]);
+        assert_eq!(top_k("cow", 3), vec![]);
+        assert_eq!(top_k("d", 3), vec![("dog".into(), 2)]);
+
+        assert_eq!(
+            trie.top_k("car".chars(), 2).collect::<Vec<_>>(),
+            vec![(vec!['c', 'a', 'r', 't'], &0), (vec!['c', 'a', 'r'], &1)]
+        );
+    }
+
+    #[test]
+    fn test_iter_keys_ordered() {
+        let mut trie = Trie::new();
+        for (word, value) in [("ab", 2), ("b", 1), ("abc", 0)] {
+            trie.push(word.chars(), value);
+        }
+
+        assert_eq!(
+            trie.iter_keys_ordered(None).collect::<Vec<_>>(),
+            vec![
+                (vec!['a', 'b', 'c'], &0),
+                (vec!['b'], &1),
+                (vec!['a', 'b'], &2)
+            ]
+        );
+        assert_eq!(
+            trie.iter_strings_ordered(None).collect::<Vec<_>>(),
+            vec![("abc".into(), &0), ("b".into(), &1), ("ab".into(), &2)]
+        );
+    }
+
+    #[test]
+    fn test_iter_strings_within() {
+        let mut trie = Trie::new();
+        let words = [("cat", 3), ("car", 1), ("cart", 4), ("dog", 2)];
+        for (word, cost) in words {
+            trie.push(word.chars(), cost);
+        }
+
+        let within = |pattern: Option<&str>, ceiling| {
+            let pattern = pattern.map(|p| Pattern::try_from(p).unwrap());
+            trie.iter_strings_within(pattern, ceiling)
+                .map(|(word, v)| (word, *v))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            within(None, 3),
+            vec![("car".into(), 1), ("dog".into(), 2), ("cat".into(), 3)]
+        );
+        assert_eq!(within(Some("ca?"), 2), vec![("car".into(), 1)]);
+        assert_eq!(within(None, 0), vec![]);
+        assert_eq!(within(None, 10).len(), 4);
+    }
+
+    #[test]
+    fn test_iter_keys_budgeted() {
+        let mut trie = Trie::new();
+        for (word, cost) in [("ab", 2), ("b", 1), ("abc", 0)] {
+            trie.push(word.chars(), cost);
+        }
+
+        // Start, a, b, c, End reaches "abc"
+        let mut budgeted = trie.iter_keys_budgeted(None, 5);
+        assert_eq!(budgeted.next(), Some((vec!['a', 'b', 'c'], &0)));
+        assert_eq!(budgeted.next(), None);
+        assert!(budgeted.budget_exhausted());
+
+        // the trie has 8 nodes, so this budget is just enough
+        let mut budgeted = trie.iter_keys_budgeted(None, 8);
+        assert_eq!(budgeted.by_ref().count(), 3);
+        assert!(!budgeted.budget_exhausted());
+
+        let mut budgeted = trie.iter_keys_budgeted(None, 0);
+        assert_eq!(budgeted.next(), None);
+        assert!(budgeted.budget_exhausted());
+
+        // an empty trie has nothing left to visit
+        let empty = Trie::<char, u8>::new();
+        let mut budgeted = empty.iter_keys_budgeted(None, 0);
+        assert_eq!(budgeted.next(), None);
+        assert!(!budgeted.budget_exhausted());
+    }
+
+    /// the ceiling only drops words, and only those above it
+    #[quickcheck]
+    fn test_iter_within_matches_ordered(words: HashSet<Vec<u8>>, ceiling: u8) {
+        let mut trie = Trie::new();
+        for word in &words {
+            trie.push(word.clone(), word.iter().max().copied().unwrap_or(0));
+        }
+
+        let within = trie
+            .iter_keys_within(None, ceiling)
+            .map(|(_, v)| *v)
+            .collect::<Vec<_>>();
+        let filtered = trie
+            .iter_values_ordered(None)
+            .map(|(_, v)| *v)
+            .filter(|v| *v <= ceiling)
+            .collect::<Vec<_>>();
+
+        assert_eq!(within, filtered);
+    }
+
     /// check if the iterator is sorted
     #[quickcheck]
     fn test_iter_inorder_2(unique_keys: HashSet<Vec<u8>>) {
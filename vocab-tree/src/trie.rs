@@ -1,73 +1,291 @@
 use std::fmt::Debug;
 
-#[derive(Debug, Clone)]
-pub struct Trie<K, V> {
-    nodes: Vec<Node<K, V>>,
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::aggregate::{Aggregate, Min};
+
+/// A trie that keeps a summary (by default the minimum) of the values of all
+/// the words below each node.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "K: Serialize, V: Serialize, A::Summary: Serialize",
+        deserialize = "K: Deserialize<'de>, V: Deserialize<'de>, \
+                       A::Summary: Deserialize<'de>"
+    ))
+)]
+pub struct Trie<K, V, A: Aggregate<V> = Min> {
+    nodes: Vec<Node<K, V, A>>,
+    /// slots in `nodes` left behind by removed words, reused by `push`
+    free: Vec<usize>,
 }
 
-#[derive(Debug, Clone)]
-pub(crate) struct Node<K, V> {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "K: Serialize, V: Serialize, A::Summary: Serialize",
+        deserialize = "K: Deserialize<'de>, V: Deserialize<'de>, \
+                       A::Summary: Deserialize<'de>"
+    ))
+)]
+pub(crate) struct Node<K, V, A: Aggregate<V>> {
     key: Key<K>,
-    min_descendent: V,
+    summary: A::Summary,
+    /// INVARIANT `Some` exactly for `Key::End` nodes
+    value: Option<V>,
     children: Vec<usize>,
     /// INVARIANT `None` if root
     parent: Option<usize>,
 }
 
-impl<K, V> Node<K, V> {
-    fn new(parent: Option<usize>, key: Key<K>, value: V) -> Self {
+impl<K, V, A: Aggregate<V>> Node<K, V, A> {
+    fn new(parent: Option<usize>, key: Key<K>, summary: A::Summary) -> Self {
         Self {
             children: Vec::new(),
-            min_descendent: value,
+            summary,
+            value: None,
             parent,
             key,
         }
     }
 
-    fn root(value: V) -> Self {
-        Self::new(None, Key::Start, value)
+    fn root(summary: A::Summary) -> Self {
+        Self::new(None, Key::Start, summary)
     }
 
     pub(crate) fn key(&self) -> &Key<K> {
         &self.key
     }
 
-    pub(crate) fn value(&self) -> &V {
-        &self.min_descendent
+    /// the summary of every word below this node
+    pub(crate) fn summary(&self) -> &A::Summary {
+        &self.summary
+    }
+
+    /// the value of the word ending here
+    pub(crate) fn value(&self) -> Option<&V> {
+        self.value.as_ref()
     }
 
     pub(crate) fn parent<'a>(
         &'a self,
-        trie: &'a Trie<K, V>,
-    ) -> Option<&'a Node<K, V>> {
+        trie: &'a Trie<K, V, A>,
+    ) -> Option<&'a Node<K, V, A>> {
         self.parent.map(move |i| trie.node(i))
     }
 }
 
+// the aggregate itself is only a marker, so these are written out by hand to
+// avoid requiring `A: Debug + Clone`
+
+impl<K: Debug, V: Debug, A> Debug for Trie<K, V, A>
+where
+    A: Aggregate<V>,
+    A::Summary: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Trie")
+            .field("nodes", &self.nodes)
+            .field("free", &self.free)
+            .finish()
+    }
+}
+
+impl<K: Clone, V: Clone, A: Aggregate<V>> Clone for Trie<K, V, A> {
+    fn clone(&self) -> Self {
+        Self {
+            nodes: self.nodes.clone(),
+            free: self.free.clone(),
+        }
+    }
+}
+
+impl<K: Debug, V: Debug, A> Debug for Node<K, V, A>
+where
+    A: Aggregate<V>,
+    A::Summary: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Node")
+            .field("key", &self.key)
+            .field("summary", &self.summary)
+            .field("value", &self.value)
+            .field("children", &self.children)
+            .field("parent", &self.parent)
+            .finish()
+    }
+}
+
+impl<K: Clone, V: Clone, A: Aggregate<V>> Clone for Node<K, V, A> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            summary: self.summary.clone(),
+            value: self.value.clone(),
+            children: self.children.clone(),
+            parent: self.parent,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub(crate) enum Key<K> {
     Start,
     Internal(K),
     End,
 }
 
-impl<K, V> Default for Trie<K, V> {
+impl<K, V, A: Aggregate<V>> Default for Trie<K, V, A> {
     fn default() -> Self {
-        Self::new()
+        Self {
+            nodes: vec![],
+            free: vec![],
+        }
     }
 }
 
-impl<K, V> Trie<K, V> {
+impl<K, V: Clone + Ord> Trie<K, V> {
+    /// An empty trie summarizing words by their smallest value. Use
+    /// [`Trie::default`] for other aggregates.
     pub fn new() -> Self {
-        Self { nodes: vec![] }
+        Self::default()
     }
+}
 
-    pub(crate) fn node(&self, index: usize) -> &Node<K, V> {
+impl<K, V, A: Aggregate<V>> Trie<K, V, A> {
+    pub(crate) fn node(&self, index: usize) -> &Node<K, V, A> {
         &self.nodes[index]
     }
 
-    pub(crate) fn root(&self) -> Option<&Node<K, V>> {
-        self.nodes.get(0)
+    pub(crate) fn root(&self) -> Option<&Node<K, V, A>> {
+        self.nodes.first()
+    }
+
+    /// Lays the nodes out in depth-first order, so that every subtree is a
+    /// contiguous run of nodes, and drops the slots freed by `remove`.
+    pub fn compact(&mut self) {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        let mut order = Vec::with_capacity(self.nodes.len() - self.free.len());
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            order.push(index);
+            stack.extend(self.nodes[index].children.iter().rev());
+        }
+
+        let mut new_indices = vec![usize::MAX; self.nodes.len()];
+        for (new_index, &old_index) in order.iter().enumerate() {
+            new_indices[old_index] = new_index;
+        }
+
+        let mut old_nodes = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        self.nodes = order
+            .into_iter()
+            .map(|old_index| {
+                let mut node = old_nodes[old_index].take().unwrap();
+                node.parent = node.parent.map(|i| new_indices[i]);
+                for i in &mut node.children {
+                    *i = new_indices[*i];
+                }
+                node
+            })
+            .collect();
+        self.free.clear();
+    }
+
+    /// Checks that every index points at a node, that the nodes reachable from
+    /// the root form a tree, and that the keys and values of those nodes are
+    /// consistent, so a decoded trie can't panic or yield bogus words.
+    #[cfg(feature = "serde")]
+    pub(crate) fn check_invariants(&self) -> Result<(), &'static str>
+    where
+        K: PartialEq,
+    {
+        let in_bounds = |i: usize| i < self.nodes.len();
+
+        let Some(root) = self.root() else {
+            return match self.free.is_empty() {
+                true => Ok(()),
+                false => Err("Free slots in an empty trie."),
+            };
+        };
+        if root.parent.is_some() || !matches!(root.key, Key::Start) {
+            return Err("The root is not at index 0.");
+        }
+        if root.value.is_some() {
+            return Err("The root has a value.");
+        }
+
+        for node in &self.nodes {
+            let parent = node.parent.into_iter();
+            if !parent.chain(node.children.iter().copied()).all(in_bounds) {
+                return Err("Node index out of bounds.");
+            }
+        }
+        if !self.free.iter().all(|&i| i != 0 && in_bounds(i)) {
+            return Err("Free slot index out of bounds.");
+        }
+
+        // every linked child must point back at its parent and be linked only
+        // once, which also rules out the root or a cycle appearing below it
+        let mut linked = vec![false; self.nodes.len()];
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let children = &self.nodes[index].children;
+            for (position, &child) in children.iter().enumerate() {
+                let node = &self.nodes[child];
+                if node.parent != Some(index) || linked[child] {
+                    return Err("Nodes do not form a tree.");
+                }
+                linked[child] = true;
+                stack.push(child);
+
+                // lookups take the first child with a matching key
+                let siblings = &children[..position];
+                if siblings.iter().any(|&i| self.nodes[i].key == node.key) {
+                    return Err("Duplicate child keys.");
+                }
+                // only `Key::End` leaves hold values, and they all do
+                match (&node.key, &node.value) {
+                    (Key::Internal(_), None) => {}
+                    (Key::End, Some(_)) if node.children.is_empty() => {}
+                    _ => return Err("Values don't match the word ends."),
+                }
+            }
+        }
+        if self.free.iter().any(|&i| linked[i]) {
+            return Err("Free slot still in use.");
+        }
+
+        Ok(())
+    }
+
+    /// number of nodes in use, not counting the slots freed by `remove`
+    pub(crate) fn node_count(&self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    /// bytes allocated for the nodes and their child lists, not counting any
+    /// heap memory owned by the keys and values themselves
+    pub(crate) fn heap_bytes(&self) -> usize {
+        let children = self
+            .nodes
+            .iter()
+            .map(|node| node.children.capacity())
+            .sum::<usize>();
+
+        self.nodes.capacity() * size_of::<Node<K, V, A>>()
+            + children * size_of::<usize>()
+            + self.free.capacity() * size_of::<usize>()
     }
 
     /// used in the traversals
@@ -77,7 +295,7 @@ impl<K, V> Trie<K, V> {
 
     pub(crate) fn path_to_root<'a>(
         &'a self,
-        node: &'a Node<K, V>,
+        node: &'a Node<K, V, A>,
     ) -> impl Iterator<Item = &'a K> {
         let mut current = node;
 
@@ -95,32 +313,48 @@ impl<K, V> Trie<K, V> {
     }
 }
 
-impl<K, V> Trie<K, V>
+impl<K, V, A> Trie<K, V, A>
 where
     K: PartialEq,
+    A: Aggregate<V>,
 {
     /// retrieve the value of a particular key sequence
-    pub fn get(
+    pub fn get(&self, keys: impl IntoIterator<Item = K>) -> Option<&V> {
+        self.get_end_index(keys).and_then(|i| self.node(i).value())
+    }
+
+    /// the summary of every word starting with `prefix`
+    pub fn summary(
         &self,
-        keys: impl IntoIterator<Item = impl Into<Key<K>>>,
-    ) -> Option<&V> {
+        prefix: impl IntoIterator<Item = K>,
+    ) -> Option<&A::Summary> {
+        let keys = prefix.into_iter().map(Key::Internal);
+
+        self.get_node_index(keys).map(|i| self.node(i).summary())
+    }
+
+    /// index of the `Key::End` node of a word
+    fn get_end_index(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Option<usize> {
         let keys = keys
             .into_iter()
-            .map(|k| k.into())
+            .map(Key::Internal)
             .chain(std::iter::once(Key::End));
 
         self.get_node_index(keys)
-            .map(|i| &self.node(i).min_descendent)
     }
 
     pub(crate) fn get_node_index(
         &self,
         keys: impl IntoIterator<Item = Key<K>>,
     ) -> Option<usize> {
+        self.root()?;
         let mut current_index = 0;
 
         for k in keys {
-            match self.get_child_index(dbg!(current_index), &k) {
+            match self.get_child_index(current_index, &k) {
                 Some(child_index) => {
                     current_index = child_index;
                 }
@@ -137,7 +371,6 @@ where
         parent_index: usize,
         child_key: &Key<K>,
     ) -> Option<usize> {
-        // get the index of the child if it exists
         self.node(parent_index)
             .children
             .iter()
@@ -146,81 +379,236 @@ where
     }
 }
 
-impl<K, V> Trie<K, V>
+impl<K, V, A> Trie<K, V, A>
 where
-    K: Clone + PartialEq + Debug,
-    V: Clone + Ord + Debug,
+    K: PartialEq,
+    A: Aggregate<V>,
 {
+    /// Adds a word, replacing its value if it's already in the trie.
     pub fn push(&mut self, keys: impl IntoIterator<Item = K>, value: V) {
+        let summary = A::single(&value);
+
         // push root if this the first element
-        if self.nodes.is_empty() {
-            self.nodes.push(Node::root(value.clone()));
+        let mut path = if self.nodes.is_empty() {
+            self.nodes.push(Node::root(summary.clone()));
+            vec![(0, true)]
         } else {
-            self.update_node_value(0, &value);
-        }
-
-        // get the root
-        let mut current_index = 0;
+            vec![(0, false)]
+        };
 
         for key in keys {
-            // PERF: unnecessary clone here
-            // change the function to take Option<&K>?
-            current_index = self.descend_to_child(
-                current_index,
-                Key::Internal(key.clone()),
-                &value,
-            );
+            let (parent_index, _) = path[path.len() - 1];
+            path.push(self.descend_to_child(
+                parent_index,
+                Key::Internal(key),
+                &summary,
+            ));
         }
 
         // add a special `Key::End` value to indicate that this is the end of a word
-        self.descend_to_child(current_index, Key::End, &value);
+        let (parent_index, _) = path[path.len() - 1];
+        let (end_index, created) =
+            self.descend_to_child(parent_index, Key::End, &summary);
+        self.nodes[end_index].value = Some(value);
+
+        if created {
+            // only the nodes that were already there need to take the new
+            // word into account
+            for (index, _) in path.into_iter().filter(|(_, created)| !created) {
+                let node = &mut self.nodes[index];
+                node.summary = A::combine(&node.summary, &summary);
+            }
+        } else {
+            // the old value could have been what determined the summaries
+            self.nodes[end_index].summary = summary;
+            self.recompute_ancestors(end_index);
+        }
     }
 
-    /// Returns the index of a child, creating the child if it doesn't exist.
-    /// Updates `min_descendent` of the child.
+    /// Changes the value of a word in place. Returns `false` (and does
+    /// nothing) if the word isn't in the trie.
+    pub fn update(
+        &mut self,
+        keys: impl IntoIterator<Item = K>,
+        f: impl FnOnce(&mut V),
+    ) -> bool {
+        let Some(end_index) = self.get_end_index(keys) else {
+            return false;
+        };
+
+        let node = &mut self.nodes[end_index];
+        if let Some(value) = node.value.as_mut() {
+            f(value);
+            node.summary = A::single(value);
+        }
+        self.recompute_ancestors(end_index);
+
+        true
+    }
+
+    /// Removes a word, returning its value if it was in the trie. Nodes that
+    /// no longer lead to any word are freed.
+    pub fn remove(&mut self, keys: impl IntoIterator<Item = K>) -> Option<V> {
+        let end_index = self.get_end_index(keys)?;
+        let value = self.nodes[end_index].value.take();
+
+        // unlink nodes bottom-up until reaching one that still has children
+        let mut index = end_index;
+        while let Some(parent_index) = self.nodes[index].parent {
+            self.nodes[parent_index].children.retain(|&i| i != index);
+            self.free.push(index);
+
+            if !self.nodes[parent_index].children.is_empty() {
+                self.recompute_summary(parent_index);
+                self.recompute_ancestors(parent_index);
+                return value;
+            }
+            index = parent_index;
+        }
+
+        // that was the last word
+        self.nodes.clear();
+        self.free.clear();
+        value
+    }
+
+    /// Returns the index of a child, creating the child with `summary` if it
+    /// doesn't exist, along with whether it was created.
     fn descend_to_child(
         &mut self,
         parent_index: usize,
         child_key: Key<K>,
-        child_value: &V,
+        summary: &A::Summary,
+    ) -> (usize, bool) {
+        match self.get_child_index(parent_index, &child_key) {
+            Some(child_index) => (child_index, false),
+            None => {
+                let child_index =
+                    self.add_child(parent_index, child_key, summary.clone());
+                (child_index, true)
+            }
+        }
+    }
+
+    /// Creates a new child, reusing a freed slot if there is one.
+    fn add_child(
+        &mut self,
+        parent_index: usize,
+        child_key: Key<K>,
+        summary: A::Summary,
     ) -> usize {
-        // get the index of the child if it exists
-        let maybe_index = self
-            .child_indices(parent_index)
-            .iter()
-            .find(|&i| child_key == self.nodes[*i].key)
-            .cloned();
+        let child = Node::new(Some(parent_index), child_key, summary);
 
-        match maybe_index {
-            Some(child_index) => {
-                self.update_node_value(child_index, child_value);
-                child_index
+        let child_index = match self.free.pop() {
+            Some(free_index) => {
+                self.nodes[free_index] = child;
+                free_index
             }
             None => {
-                let child = Node::new(
-                    Some(parent_index),
-                    child_key,
-                    child_value.clone(),
-                );
                 self.nodes.push(child);
+                self.nodes.len() - 1
+            }
+        };
 
-                let child_index = self.nodes.len() - 1;
-                self.nodes[parent_index].children.push(child_index);
+        self.nodes[parent_index].children.push(child_index);
 
-                child_index
-            }
+        child_index
+    }
+
+    /// Recomputes the summary of an internal node from its children.
+    fn recompute_summary(&mut self, node_index: usize) {
+        let summary = self.nodes[node_index]
+            .children
+            .iter()
+            .map(|&i| &self.nodes[i].summary)
+            .fold(None, |acc: Option<A::Summary>, s| match acc {
+                None => Some(s.clone()),
+                Some(acc) => Some(A::combine(&acc, s)),
+            });
+
+        if let Some(summary) = summary {
+            self.nodes[node_index].summary = summary;
         }
     }
 
-    fn update_node_value(&mut self, node_index: usize, new_value: &V) -> bool {
-        let node = self.nodes.get_mut(node_index).unwrap();
+    /// Recomputes the summaries of every node above `node_index`.
+    fn recompute_ancestors(&mut self, node_index: usize) {
+        let mut current = self.nodes[node_index].parent;
+        while let Some(index) = current {
+            self.recompute_summary(index);
+            current = self.nodes[index].parent;
+        }
+    }
+}
 
-        if *new_value < node.min_descendent {
-            node.min_descendent = new_value.clone();
-            true
-        } else {
-            false
+impl<K, V, A> Trie<K, V, A>
+where
+    K: Clone + Ord,
+    A: Aggregate<V>,
+{
+    /// Builds a trie from words in strictly increasing order, without
+    /// searching for any children along the way.
+    pub fn from_sorted<I, W>(words: I) -> Result<Self, &'static str>
+    where
+        I: IntoIterator<Item = (W, V)>,
+        W: IntoIterator<Item = K>,
+    {
+        let mut trie = Self::default();
+
+        // the previous word and the nodes along it, starting with the root
+        let mut previous: Vec<K> = Vec::new();
+        let mut path = Vec::new();
+
+        for (word, value) in words {
+            let word = word.into_iter().collect::<Vec<_>>();
+            let summary = A::single(&value);
+            let common = word
+                .iter()
+                .zip(&previous)
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            if trie.nodes.is_empty() {
+                trie.nodes.push(Node::root(summary.clone()));
+                path.push(0);
+            } else {
+                let in_order = match (word.get(common), previous.get(common)) {
+                    (Some(k), Some(previous_k)) => k > previous_k,
+                    // the previous word is a prefix of this one
+                    (Some(_), None) => true,
+                    // this word is a prefix of (or equal to) the previous one
+                    (None, _) => false,
+                };
+                if !in_order {
+                    return Err("Words must be inserted in sorted order.");
+                }
+
+                // the shared prefix already exists, so only needs to take
+                // the new word into account
+                for &index in &path[..=common] {
+                    let node = &mut trie.nodes[index];
+                    node.summary = A::combine(&node.summary, &summary);
+                }
+            }
+
+            path.truncate(common + 1);
+            for key in &word[common..] {
+                let parent_index = path[path.len() - 1];
+                path.push(trie.add_child(
+                    parent_index,
+                    Key::Internal(key.clone()),
+                    summary.clone(),
+                ));
+            }
+
+            let parent_index = path[path.len() - 1];
+            let end_index = trie.add_child(parent_index, Key::End, summary);
+            trie.nodes[end_index].value = Some(value);
+
+            previous = word;
         }
+
+        Ok(trie)
     }
 }
 
@@ -232,8 +620,10 @@ impl<K> From<K> for Key<K> {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::collections::{BTreeMap, HashSet};
 
+    use itertools::Itertools;
+    use quickcheck::{Arbitrary, Gen};
     use quickcheck_macros::quickcheck;
 
     use super::*;
@@ -253,13 +643,13 @@ mod tests {
         let mut trie = Trie::new();
         trie.push("car".chars(), 10);
 
-        assert_eq!(trie.root().map(|n| n.value().clone()), Some(10));
+        assert_eq!(trie.root().map(|n| *n.summary()), Some(10));
 
         trie.push("cat".chars(), 5);
-        assert_eq!(trie.root().map(|n| n.value().clone()), Some(5));
+        assert_eq!(trie.root().map(|n| *n.summary()), Some(5));
 
         trie.push("cab".chars(), 7);
-        assert_eq!(trie.root().map(|n| n.value().clone()), Some(5));
+        assert_eq!(trie.root().map(|n| *n.summary()), Some(5));
     }
 
     #[test]
@@ -294,6 +684,213 @@ mod tests {
 
         assert!(trie.get("c".chars()).is_none());
         assert!(trie.get("ca".chars()).is_none());
+
+        assert!(Trie::<char, ()>::new().get("car".chars()).is_none());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut trie = Trie::new();
+        trie.push("car".chars(), 1);
+        trie.push("cart".chars(), 2);
+        trie.push("cat".chars(), 3);
+        let len = trie.nodes.len();
+
+        assert_eq!(trie.remove("ca".chars()), None);
+        assert_eq!(trie.remove("car".chars()), Some(1));
+        assert_eq!(trie.remove("car".chars()), None);
+        assert_eq!(trie.get("cart".chars()), Some(&2));
+        assert_eq!(trie.root().map(|n| *n.summary()), Some(2));
+
+        // "cart" and its nodes are freed and reused
+        assert_eq!(trie.remove("cart".chars()), Some(2));
+        assert_eq!(trie.root().map(|n| *n.summary()), Some(3));
+        trie.push("cord".chars(), 4);
+        assert_eq!(trie.nodes.len(), len);
+
+        trie.remove("cat".chars());
+        trie.remove("cord".chars());
+        assert!(trie.root().is_none());
+        assert_eq!(trie.iter_values_unordered(None).count(), 0);
+    }
+
+    #[test]
+    fn test_update() {
+        let mut trie = Trie::new();
+        trie.push("car".chars(), 1);
+        trie.push("cat".chars(), 3);
+
+        assert!(trie.update("car".chars(), |v| *v = 5));
+        assert!(!trie.update("ca".chars(), |v| *v = 0));
+        assert_eq!(trie.get("car".chars()), Some(&5));
+        assert_eq!(trie.root().map(|n| *n.summary()), Some(3));
+
+        // pushing an existing word with a worse value also raises the summary
+        trie.push("cat".chars(), 7);
+        assert_eq!(trie.root().map(|n| *n.summary()), Some(5));
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Push(Vec<u8>, u8),
+        Update(Vec<u8>, u8),
+        Remove(Vec<u8>),
+    }
+
+    impl Arbitrary for Op {
+        fn arbitrary(g: &mut Gen) -> Self {
+            // a small alphabet so that operations hit the same words
+            let key = Vec::<bool>::arbitrary(g)
+                .into_iter()
+                .map(u8::from)
+                .collect();
+
+            match u8::arbitrary(g) % 3 {
+                0 => Op::Push(key, u8::arbitrary(g)),
+                1 => Op::Update(key, u8::arbitrary(g)),
+                _ => Op::Remove(key),
+            }
+        }
+    }
+
+    /// ordered iteration stays sorted and agrees with a `BTreeMap`
+    #[quickcheck]
+    fn test_interleaved_ops(ops: Vec<Op>) {
+        let mut trie = Trie::new();
+        let mut model = BTreeMap::new();
+
+        for op in ops {
+            match op {
+                Op::Push(key, value) => {
+                    trie.push(key.clone(), value);
+                    model.insert(key, value);
+                }
+                Op::Update(key, value) => {
+                    let updated = trie.update(key.clone(), |v| *v = value);
+                    assert_eq!(updated, model.contains_key(&key));
+                    if let Some(v) = model.get_mut(&key) {
+                        *v = value;
+                    }
+                }
+                Op::Remove(key) => {
+                    assert_eq!(trie.remove(key.clone()), model.remove(&key));
+                }
+            }
+        }
+
+        let values = trie
+            .iter_keys_ordered(None)
+            .map(|(key, v)| (key, *v))
+            .collect::<Vec<_>>();
+        assert!(values.is_sorted_by_key(|(_, v)| *v));
+
+        let values = values.into_iter().collect::<BTreeMap<_, _>>();
+        assert_eq!(values, model);
+        assert_eq!(
+            trie.root().map(|n| *n.summary()),
+            model.values().min().copied()
+        );
+    }
+
+    #[test]
+    fn test_from_sorted() {
+        let words = [("car", 3), ("cart", 1), ("cat", 2), ("dog", 4)];
+        let sorted =
+            Trie::<_, _>::from_sorted(words.map(|(w, v)| (w.chars(), v)))
+                .unwrap();
+
+        let mut pushed = Trie::new();
+        for (word, value) in words {
+            pushed.push(word.chars(), value);
+        }
+
+        assert_eq!(sorted.nodes.len(), pushed.nodes.len());
+        assert_eq!(sorted.root().map(|n| *n.summary()), Some(1));
+        assert_eq!(sorted.summary("ca".chars()), Some(&1));
+        assert_eq!(sorted.get("cat".chars()), Some(&2));
+        assert_eq!(
+            sorted.iter_strings_ordered(None).collect::<Vec<_>>(),
+            pushed.iter_strings_ordered(None).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_from_sorted_order() {
+        let from_sorted = |words: &[&str]| {
+            Trie::<_, _>::from_sorted(words.iter().map(|w| (w.chars(), ())))
+        };
+
+        assert!(from_sorted(&[]).is_ok());
+        assert!(from_sorted(&["", "a", "ab", "b"]).is_ok());
+        assert!(from_sorted(&["b", "a"]).is_err());
+        assert!(from_sorted(&["ab", "a"]).is_err());
+        assert!(from_sorted(&["a", "a"]).is_err());
+    }
+
+    #[quickcheck]
+    fn test_from_sorted_matches_push(words: BTreeMap<Vec<u8>, u8>) {
+        let sorted = Trie::<_, _>::from_sorted(words.clone()).unwrap();
+
+        let mut pushed = Trie::new();
+        for (word, value) in &words {
+            pushed.push(word.clone(), *value);
+        }
+
+        let mut from_sorted = sorted.iter_keys_unordered(None).collect_vec();
+        let mut from_push = pushed.iter_keys_unordered(None).collect_vec();
+        from_sorted.sort();
+        from_push.sort();
+
+        assert_eq!(from_sorted, from_push);
+        assert_eq!(sorted.nodes.len(), pushed.nodes.len());
+        assert!(sorted
+            .iter_values_ordered(None)
+            .is_sorted_by_key(|(_, v)| *v));
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut trie = Trie::new();
+        for (word, value) in [("dog", 4), ("car", 3), ("cart", 1), ("do", 2)] {
+            trie.push(word.chars(), value);
+        }
+        trie.remove("cart".chars());
+        trie.push("cat".chars(), 5);
+        trie.remove("do".chars());
+
+        let words = |trie: &Trie<char, i32>| {
+            let words = trie.iter_strings_unordered(None);
+            words.map(|(w, v)| (w, *v)).sorted().collect_vec()
+        };
+
+        let before = words(&trie);
+        trie.compact();
+        assert_eq!(before, words(&trie));
+        assert!(trie.free.is_empty());
+        assert_eq!(
+            trie.iter_strings_ordered(None).collect_vec(),
+            vec![("car".into(), &3), ("dog".into(), &4), ("cat".into(), &5)]
+        );
+
+        // pre-order: every subtree is a contiguous run of nodes, and each
+        // node's parent comes before it
+        let mut stack = vec![0];
+        let mut order = vec![];
+        while let Some(i) = stack.pop() {
+            order.push(i);
+            stack.extend(trie.child_indices(i).iter().rev());
+        }
+        assert_eq!(order, (0..trie.nodes.len()).collect_vec());
+        assert!(trie
+            .nodes
+            .iter()
+            .skip(1)
+            .enumerate()
+            .all(|(i, n)| { n.parent.is_some_and(|p| p <= i) }));
+
+        let mut empty = Trie::<char, ()>::new();
+        empty.compact();
+        assert!(empty.root().is_none());
     }
 
     #[quickcheck]
@@ -306,4 +903,51 @@ mod tests {
 
         assert_eq!(trie.iter_values_unordered(None).count(), strings.len());
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_corrupt_keys_and_values() {
+        let build = || {
+            let mut trie = Trie::new();
+            trie.push("car".chars(), 3);
+            trie.push("cat".chars(), 1);
+            trie
+        };
+        let read_back = |trie: &Trie<char, i32>| {
+            let mut bytes = Vec::new();
+            trie.write_snapshot(&mut bytes).unwrap();
+            Trie::<char, i32>::read_snapshot(bytes.as_slice())
+        };
+        let find = |trie: &Trie<char, i32>, key: Key<char>| {
+            trie.nodes.iter().position(|n| n.key == key).unwrap()
+        };
+        assert!(read_back(&build()).is_ok());
+
+        // "ca" would come out as a word
+        let mut trie = build();
+        let a = find(&trie, Key::Internal('a'));
+        trie.nodes[a].value = Some(0);
+        assert!(read_back(&trie).is_err());
+
+        // "car" would be a prefix that isn't a word
+        let mut trie = build();
+        let end = find(&trie, Key::End);
+        trie.nodes[end].value = None;
+        assert!(read_back(&trie).is_err());
+
+        let mut trie = build();
+        let end = find(&trie, Key::End);
+        trie.nodes[end].key = Key::Internal('s');
+        assert!(read_back(&trie).is_err());
+
+        let mut trie = build();
+        let t = find(&trie, Key::Internal('t'));
+        trie.nodes[t].key = Key::Internal('r');
+        assert!(read_back(&trie).is_err());
+
+        let mut trie = build();
+        let c = find(&trie, Key::Internal('c'));
+        trie.nodes[c].key = Key::Start;
+        assert!(read_back(&trie).is_err());
+    }
 }